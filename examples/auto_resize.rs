@@ -0,0 +1,35 @@
+//! Demonstrates a terminal which resizes to fill the window.
+
+use bevy::prelude::*;
+use bevy_ascii_terminal::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .add_systems(Startup, setup)
+        .add_systems(Update, redraw)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Terminal::new([2, 2]),
+        TerminalBorder::single_line(),
+        TerminalAutoResize::new(2),
+    ));
+    commands.spawn(TerminalCamera::new());
+}
+
+fn redraw(
+    mut evt: EventReader<TerminalResizedEvent>,
+    mut q_term: Query<(&mut Terminal, &mut TerminalBorder)>,
+) {
+    for evt in evt.read() {
+        let Ok((mut term, mut border)) = q_term.get_mut(evt.entity) else {
+            continue;
+        };
+        term.put_string([0, 0].pivot(Pivot::Center), "Resize the window!");
+        border.clear_strings();
+        border.put_title(format!("{}x{}", evt.size.x, evt.size.y));
+    }
+}
@@ -31,6 +31,7 @@ fn main() {
             Update,
             (
                 handle_just_pressed,
+                handle_camera,
                 handle_pressed.run_if(on_timer(key_repeat)),
             ),
         )
@@ -56,7 +57,10 @@ fn setup(mut commands: Commands) {
         make_terminal([12, 12], FADED),
         TerminalMeshPivot::TopCenter,
         TerminalBorder::single_line(),
-        TermString("Space to toggle border".to_string(), Pivot::TopCenter),
+        TermString(
+            "Space to toggle border\n1-4 to change scaling\nF to frame active terminal".to_string(),
+            Pivot::TopCenter,
+        ),
     ));
 }
 
@@ -121,6 +125,32 @@ fn handle_just_pressed(
     }
 }
 
+fn handle_camera(
+    mut q_cam: Query<&mut TerminalCamera>,
+    q_term: Query<Entity, With<Terminal>>,
+    input: Res<ButtonInput<KeyCode>>,
+    current: Res<Current>,
+) {
+    let mut cam = q_cam.single_mut();
+    let scaling = [
+        (KeyCode::Digit1, TerminalCameraScaling::Integer),
+        (KeyCode::Digit2, TerminalCameraScaling::Fit),
+        (KeyCode::Digit3, TerminalCameraScaling::Fill),
+        (KeyCode::Digit4, TerminalCameraScaling::Expand),
+    ];
+    for (key, scaling) in scaling {
+        if input.just_pressed(key) {
+            cam.scaling = scaling;
+        }
+    }
+    if input.just_pressed(KeyCode::KeyF) {
+        cam.frame_target = match cam.frame_target {
+            Some(_) => None,
+            None => q_term.iter().sort::<Entity>().nth(current.0),
+        };
+    }
+}
+
 fn handle_pressed(
     mut q_term: Query<(&mut Terminal, &TermString)>,
     input: Res<ButtonInput<KeyCode>>,
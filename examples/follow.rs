@@ -0,0 +1,84 @@
+//! Demonstrates a camera smoothly following a player over a large map
+//! terminal. Use the arrow keys to move and +/- to zoom.
+
+use bevy::prelude::*;
+use bevy_ascii_terminal::*;
+use rand::Rng;
+
+const MAP_SIZE: [u32; 2] = [120, 80];
+
+#[derive(Resource)]
+struct Player(IVec2);
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .insert_resource(Player(IVec2::new(60, 40)))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (input, draw).chain())
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    let map = commands
+        .spawn((Terminal::new(MAP_SIZE), TerminalBorder::double_line()))
+        .id();
+    commands.spawn((
+        TerminalCamera::new(),
+        TerminalCameraFollow::new(map, [60, 40]).with_zoom(2),
+    ));
+}
+
+fn input(
+    input: Res<ButtonInput<KeyCode>>,
+    mut player: ResMut<Player>,
+    mut q_cam: Query<&mut TerminalCameraFollow>,
+) {
+    let mut follow = q_cam.single_mut();
+    let dir = [
+        (KeyCode::ArrowUp, IVec2::Y),
+        (KeyCode::ArrowDown, IVec2::NEG_Y),
+        (KeyCode::ArrowLeft, IVec2::NEG_X),
+        (KeyCode::ArrowRight, IVec2::X),
+    ]
+    .into_iter()
+    .filter(|(key, _)| input.just_pressed(*key))
+    .map(|(_, dir)| dir)
+    .sum::<IVec2>();
+    if dir != IVec2::ZERO {
+        let max = UVec2::from(MAP_SIZE).as_ivec2() - 1;
+        player.0 = (player.0 + dir).clamp(IVec2::ZERO, max);
+        follow.set_tile(player.0);
+    }
+    if input.just_pressed(KeyCode::Equal) {
+        follow.zoom_in();
+    }
+    if input.just_pressed(KeyCode::Minus) {
+        follow.zoom_out();
+    }
+}
+
+fn draw(player: Res<Player>, mut q_term: Query<&mut Terminal>, mut drawn: Local<bool>) {
+    if !player.is_changed() && *drawn {
+        return;
+    }
+    let mut term = q_term.single_mut();
+    if !*drawn {
+        let mut rng = rand::thread_rng();
+        for t in term.tiles_mut() {
+            let glyph = ['.', '.', '.', ',', '"', '♣'][rng.gen_range(0..6)];
+            t.glyph = glyph;
+            t.fg_color = color::DARK_GREEN;
+        }
+        *drawn = true;
+    }
+    for (p, t) in term.iter_xy_mut() {
+        if t.glyph == '@' {
+            t.glyph = '.';
+        }
+        if p == player.0 {
+            t.glyph = '@';
+            t.fg_color = color::YELLOW;
+        }
+    }
+}
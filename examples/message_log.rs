@@ -0,0 +1,45 @@
+//! Demonstrates a message log. Press space to attack, scroll back with the
+//! mouse wheel or page up/page down.
+
+use bevy::prelude::*;
+use bevy_ascii_terminal::{widget::MessageLog, *};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .add_systems(Startup, setup)
+        .add_systems(Update, attack)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    let term = commands
+        .spawn((
+            Terminal::new([30, 10]),
+            TerminalBorder::single_line().with_title("Messages"),
+        ))
+        .id();
+    let mut log = MessageLog::new(term);
+    log.push("Welcome to the dungeon! Press space to attack the rat.".fg(color::GOLD));
+    commands.spawn(log);
+    commands.spawn(TerminalCamera::new());
+}
+
+fn attack(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut q_log: Query<&mut MessageLog>,
+    mut hits: Local<u32>,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    let Ok(mut log) = q_log.get_single_mut() else {
+        return;
+    };
+    *hits += 1;
+    if (*hits).is_multiple_of(4) {
+        log.push("The rat dies. Another rat appears!".fg(color::RED));
+    } else {
+        log.push("You hit the rat".fg(color::WHITE));
+    }
+}
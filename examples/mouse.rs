@@ -0,0 +1,60 @@
+//! Demonstrates tile-level mouse events on overlapping terminals.
+
+use bevy::prelude::*;
+use bevy_ascii_terminal::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .add_systems(Startup, setup)
+        .add_systems(Update, on_mouse)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Terminal::new([30, 12]).with_string([0, 0], "Click, drag or scroll"),
+        TerminalBorder::single_line(),
+    ));
+    commands.spawn((
+        Terminal::new([12, 5]).with_clear_tile(Tile::default().with_bg(color::NAVY)),
+        TerminalBorder::double_line().with_title("Top"),
+        SetTerminalGridPosition::from([10, 4]),
+        SetTerminalLayerPosition(1),
+    ));
+    commands.spawn(TerminalCamera::new());
+}
+
+fn on_mouse(mut evt: EventReader<TerminalMouseEvent>, mut q_term: Query<&mut Terminal>) {
+    for evt in evt.read() {
+        let Ok(mut term) = q_term.get_mut(evt.entity) else {
+            continue;
+        };
+        if !term.bounds().contains_point(evt.tile) {
+            continue;
+        }
+        match evt.kind {
+            TerminalMouseEventKind::Enter => {
+                term.put_bg_color(evt.tile, color::DARK_SLATE_GRAY);
+            }
+            TerminalMouseEventKind::Leave => {
+                let bg = term.clear_tile().bg_color;
+                term.put_bg_color(evt.tile, bg);
+            }
+            TerminalMouseEventKind::Click(_) => {
+                term.put_char(evt.tile, '+').fg(color::YELLOW);
+            }
+            TerminalMouseEventKind::DoubleClick(_) => {
+                term.put_char(evt.tile, '#').fg(color::ORANGE);
+            }
+            TerminalMouseEventKind::DragMove(_) => {
+                term.put_char(evt.tile, '.').fg(color::LIGHT_BLUE);
+            }
+            TerminalMouseEventKind::Wheel(delta) => {
+                let glyph = if delta.y > 0.0 { '↑' } else { '↓' };
+                term.put_char(evt.tile, glyph).fg(color::GREEN);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Demonstrates rendering terminals to multiple windows, using render layers
+//! to assign each terminal to a camera.
+
+use bevy::{
+    prelude::*,
+    render::{camera::RenderTarget, view::RenderLayers},
+    window::WindowRef,
+};
+use bevy_ascii_terminal::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .add_systems(Startup, setup)
+        .add_systems(Update, show_cursor)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Terminal::new([30, 10]),
+        TerminalBorder::single_line().with_title("Main"),
+    ));
+    commands.spawn(TerminalCamera::new());
+
+    let console = commands
+        .spawn(Window {
+            title: "Debug Console".to_string(),
+            ..default()
+        })
+        .id();
+    commands.spawn((
+        Terminal::new([20, 6]),
+        TerminalBorder::double_line().with_title("Console"),
+        RenderLayers::layer(1),
+    ));
+    commands.spawn((
+        TerminalCamera::new(),
+        Camera {
+            target: RenderTarget::Window(WindowRef::Entity(console)),
+            ..default()
+        },
+        RenderLayers::layer(1),
+    ));
+}
+
+fn show_cursor(
+    q_cam: Query<&TerminalCamera>,
+    mut q_term: Query<(&mut Terminal, &TerminalTransform)>,
+) {
+    let cursor = q_cam.iter().find_map(|cam| cam.cursor_world_pos());
+    for (mut term, transform) in &mut q_term {
+        term.clear();
+        if let Some(tile) = cursor.and_then(|pos| transform.world_to_tile(pos)) {
+            term.put_string([0, 0], format!("Cursor: {}", tile));
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Demonstrates popup menus and dialogs. Right click a tile to open a menu,
+//! press Q to open a quit dialog.
+
+use bevy::prelude::*;
+use bevy_ascii_terminal::{widget::*, *};
+
+const OPTIONS: [&str; 3] = ["Look", "Take", "Attack"];
+
+#[derive(Component)]
+struct QuitDialog;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (open_popups, on_closed))
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Terminal::new([40, 20]).with_string([1, 1], "Right click to open a menu"),
+        TerminalBorder::single_line(),
+    ));
+    commands.spawn(TerminalCamera::new());
+}
+
+fn open_popups(
+    mut commands: Commands,
+    q_term: Query<Entity, (With<Terminal>, Without<Popup>)>,
+    mouse: Res<TerminalMouse>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    focus: Res<TerminalFocus>,
+) {
+    // Only open popups from the main terminal while nothing else has focus
+    let Ok(term) = q_term.get_single() else {
+        return;
+    };
+    if focus.focused().is_some() {
+        return;
+    }
+    if buttons.just_pressed(MouseButton::Right) {
+        if let Some((_, tile)) = mouse.hovered().filter(|(e, _)| *e == term) {
+            commands.spawn(Popup::menu(OPTIONS).at(term, tile));
+        }
+    }
+    if keys.just_pressed(KeyCode::KeyQ) {
+        commands.spawn((
+            Popup::confirm("Really quit?").at(term, [10, 12]),
+            QuitDialog,
+        ));
+    }
+}
+
+fn on_closed(
+    mut evt: EventReader<PopupClosed>,
+    q_dialog: Query<(), With<QuitDialog>>,
+    mut q_term: Query<&mut Terminal, Without<Popup>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    for evt in evt.read() {
+        let Ok(mut term) = q_term.get_single_mut() else {
+            continue;
+        };
+        let is_dialog = q_dialog.contains(evt.entity);
+        term.clear();
+        match evt.result {
+            PopupResult::Selected(0) if is_dialog => {
+                exit.send(AppExit::Success);
+            }
+            PopupResult::Selected(i) if !is_dialog => {
+                term.put_string([1, 1], format!("You chose to {}", OPTIONS[i]));
+            }
+            _ => {
+                term.put_string([1, 1], "Cancelled");
+            }
+        }
+    }
+}
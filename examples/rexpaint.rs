@@ -1,7 +1,7 @@
-//! A terminal built from a rexpaint file.
+//! A terminal built from a rexpaint file loaded through the asset server.
 
 use bevy::prelude::*;
-use bevy_ascii_terminal::*;
+use bevy_ascii_terminal::{rexpaint::XpAssetHandle, *};
 
 fn main() {
     App::new()
@@ -10,9 +10,10 @@ fn main() {
         .run();
 }
 
-fn setup(mut commands: Commands) {
+fn setup(mut commands: Commands, server: Res<AssetServer>) {
     commands.spawn((
-        Terminal::from_rexpaint_file("assets/hello_rexpaint.xp").unwrap(),
+        Terminal::new([1, 1]),
+        XpAssetHandle(server.load("hello_rexpaint.xp")),
         TerminalBorder::single_line(),
     ));
     commands.spawn(TerminalCamera::new());
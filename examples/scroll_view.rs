@@ -0,0 +1,49 @@
+//! Demonstrates a scroll view. Scroll with the mouse wheel or drag the
+//! scrollbars.
+
+use bevy::prelude::*;
+use bevy_ascii_terminal::{string::StringFormatter, widget::*, *};
+
+#[derive(Resource)]
+struct Help(ScrollView);
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .add_systems(Startup, setup)
+        .add_systems(Update, draw)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    let mut view = ScrollView::new([60, 100]);
+    for i in 0..100 {
+        view.canvas_mut().put_string(
+            [0, i],
+            format!("{:3}: The quick brown fox jumps over the lazy dog.", i).dont_word_wrap(),
+        );
+    }
+    commands.insert_resource(Help(view));
+    commands.spawn((
+        Terminal::new([30, 15]),
+        TerminalBorder::single_line().with_title("Help"),
+    ));
+    commands.spawn(TerminalCamera::new());
+}
+
+fn draw(
+    mut q_term: Query<(Entity, &mut Terminal)>,
+    mouse: Res<TerminalMouse>,
+    mut mouse_events: EventReader<TerminalMouseEvent>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut help: ResMut<Help>,
+) {
+    let Ok((entity, mut term)) = q_term.get_single_mut() else {
+        return;
+    };
+    let events: Vec<_> = mouse_events.read().collect();
+    let input = WidgetInput::new(entity, &mouse, events.iter().copied(), &keys);
+    let rect = term.bounds();
+    help.0
+        .draw(&mut term, rect, &input, &WidgetStyle::default());
+}
@@ -0,0 +1,49 @@
+//! Demonstrates a text input widget. Type a name and press enter.
+
+use bevy::prelude::*;
+use bevy_ascii_terminal::{
+    widget::{TerminalTextInput, TextInput, TextInputSubmitted},
+    *,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .add_systems(Startup, setup)
+        .add_systems(Update, on_submit)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    let term = commands
+        .spawn((
+            Terminal::new([30, 5]).with_string([1, 1], "What is your name?"),
+            TerminalBorder::single_line(),
+        ))
+        .id();
+    commands.spawn(
+        TerminalTextInput::new(term, [1, 2], 28)
+            .with_input(TextInput::default().with_max_len(40))
+            .focused(),
+    );
+    commands.spawn(TerminalCamera::new());
+}
+
+fn on_submit(
+    mut evt: EventReader<TextInputSubmitted>,
+    mut q_input: Query<&mut TerminalTextInput>,
+    mut q_term: Query<&mut Terminal>,
+) {
+    for evt in evt.read() {
+        let Ok(mut input) = q_input.get_mut(evt.entity) else {
+            continue;
+        };
+        let Ok(mut term) = q_term.get_mut(input.terminal) else {
+            continue;
+        };
+        term.clear();
+        term.put_string([1, 1], "What is your name?");
+        term.put_string([1, 3], format!("Hello, {}!", evt.text));
+        input.input.clear();
+    }
+}
@@ -0,0 +1,50 @@
+//! Demonstrates tooltips. Hover the map tiles or the legend to see them.
+
+use bevy::prelude::*;
+use bevy_ascii_terminal::{string::StringFormatter, widget::*, *};
+
+const MAP: [&str; 5] = [
+    "####################",
+    "#..........~~~.....#",
+    "#..T.......~~~..g..#",
+    "#..........~~~.....#",
+    "####################",
+];
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn describe(glyph: char) -> Option<String> {
+    let text = match glyph {
+        '#' => "A rough stone wall.",
+        '~' => "Deep water. You can't swim while wearing armor.",
+        'T' => "An old oak tree, its branches heavy with acorns.",
+        'g' => "A goblin. It looks hungry.",
+        _ => return None,
+    };
+    Some(text.to_string())
+}
+
+fn setup(mut commands: Commands) {
+    let mut term = Terminal::new([20, 8]);
+    for (i, line) in MAP.iter().enumerate() {
+        term.put_string([0, i as i32], line.dont_word_wrap());
+    }
+    term.put_string([0, 7], "Hover for help");
+    let map = term.clone();
+    let entity = commands.spawn(term).id();
+    commands.spawn(Tooltip::from_fn(entity, move |tile| {
+        (tile.y > 0)
+            .then(|| describe(map.tile(tile).glyph))
+            .flatten()
+    }));
+    commands.spawn(
+        Tooltip::text(entity, "Tooltips stay inside the camera's view.")
+            .with_rect(GridRect::new([0, 0], [14, 1])),
+    );
+    commands.spawn(TerminalCamera::new());
+}
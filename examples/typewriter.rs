@@ -0,0 +1,50 @@
+//! Demonstrates a dialogue box with text revealed one character at a time.
+//! Press enter or space to skip to the end of the text, then again to
+//! continue.
+
+use bevy::prelude::*;
+use bevy_ascii_terminal::{widget::*, *};
+
+const DIALOGUE: [&str; 3] = [
+    "Halt! Who goes there? State your business, traveller, or turn back the way you came.",
+    "The bridge is closed by order of the Duke. Nobody crosses until the bandits are dealt with.",
+    "...Fine. Go on then, but don't say I didn't warn you.",
+];
+
+#[derive(Resource, Default)]
+struct Page(usize);
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .init_resource::<Page>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, next_page)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    let term = commands
+        .spawn((
+            Terminal::new([30, 6]),
+            TerminalBorder::single_line().with_title("Guard"),
+        ))
+        .id();
+    commands.spawn(Typewriter::new(term, DIALOGUE[0].fg(color::LIGHT_YELLOW)));
+    commands.spawn(TerminalCamera::new());
+}
+
+fn next_page(
+    mut q_writer: Query<&mut Typewriter>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut page: ResMut<Page>,
+) {
+    let Ok(mut writer) = q_writer.get_single_mut() else {
+        return;
+    };
+    if !writer.is_finished() || !keys.any_just_pressed([KeyCode::Enter, KeyCode::Space]) {
+        return;
+    }
+    page.0 = (page.0 + 1) % DIALOGUE.len();
+    writer.set_text(DIALOGUE[page.0].fg(color::LIGHT_YELLOW));
+}
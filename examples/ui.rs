@@ -0,0 +1,61 @@
+//! Demonstrates displaying a terminal inside a `bevy_ui` layout.
+//!
+//! Run with `cargo run --example ui --features ui`.
+
+use bevy::prelude::*;
+use bevy_ascii_terminal::{ui::TerminalUiNode, *};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .add_systems(Startup, setup)
+        .add_systems(Update, show_cursor)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    let term = commands
+        .spawn((
+            Terminal::new([20, 12]),
+            TerminalBorder::single_line().with_title("Inventory"),
+        ))
+        .id();
+    commands.spawn(Camera2d);
+    commands
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::SpaceBetween,
+            ..default()
+        })
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    flex_grow: 1.0,
+                    ..default()
+                },
+                BackgroundColor(color::DARK_SLATE_GRAY.into()),
+            ));
+            root.spawn((
+                Node {
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                BackgroundColor(color::BLACK.into()),
+            ))
+            .with_child(TerminalUiNode::new(term).with_zoom(2));
+        });
+}
+
+fn show_cursor(q_node: Query<&TerminalUiNode>, mut q_term: Query<&mut Terminal>) {
+    for node in &q_node {
+        let Ok(mut term) = q_term.get_mut(node.terminal) else {
+            continue;
+        };
+        term.clear();
+        term.put_string([0, 0], "Hover me!");
+        if let Some(tile) = node.cursor_tile() {
+            term.put_string([0, 1], format!("Cursor: {}", tile));
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Demonstrates the immediate mode widgets. Widgets are redrawn every frame
+//! from the state stored in the `Settings` resource.
+
+use bevy::prelude::*;
+use bevy_ascii_terminal::{widget::*, *};
+
+const ITEMS: [&str; 12] = [
+    "Sword", "Shield", "Bow", "Arrows", "Potion", "Scroll", "Torch", "Rope", "Lockpick", "Ring",
+    "Amulet", "Bread",
+];
+
+#[derive(Resource, Default)]
+struct Settings {
+    sound: bool,
+    difficulty: usize,
+    volume: f32,
+    progress: f32,
+    inventory: ListState,
+    message: String,
+}
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, TerminalPlugins))
+        .init_resource::<Settings>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, draw)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((Terminal::new([40, 20]), TerminalBorder::single_line()));
+    commands.spawn(TerminalCamera::new());
+}
+
+fn draw(
+    mut q_term: Query<(Entity, &mut Terminal)>,
+    mouse: Res<TerminalMouse>,
+    mut mouse_events: EventReader<TerminalMouseEvent>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+    time: Res<Time>,
+) {
+    let Ok((entity, mut term)) = q_term.get_single_mut() else {
+        return;
+    };
+    let events: Vec<_> = mouse_events.read().collect();
+    let input = WidgetInput::new(entity, &mouse, events.iter().copied(), &keys);
+    let style = WidgetStyle::default();
+    let settings = settings.as_mut();
+    term.clear();
+
+    checkbox(
+        &mut term,
+        GridRect::new([1, 18], [16, 1]),
+        "Sound",
+        &mut settings.sound,
+        &input,
+        &style,
+    );
+    radio_group(
+        &mut term,
+        GridRect::new([1, 14], [16, 3]),
+        &["Easy", "Normal", "Hard"],
+        &mut settings.difficulty,
+        &input,
+        &style,
+    );
+    slider(
+        &mut term,
+        GridRect::new([1, 12], [16, 1]),
+        &mut settings.volume,
+        0.0..=1.0,
+        &input,
+        &style,
+    );
+    term.put_string(
+        [1, 8].pivot(Pivot::BottomLeft),
+        format!("Volume {:3.0}%", settings.volume * 100.0),
+    );
+
+    settings.progress = (settings.progress + time.delta_secs() * 0.1).fract();
+    progress_bar(
+        &mut term,
+        GridRect::new([1, 6], [16, 1]),
+        settings.progress,
+        &style,
+    );
+
+    if button(
+        &mut term,
+        GridRect::new([1, 2], [16, 3]),
+        "Reset",
+        &input,
+        &style,
+    ) {
+        settings.volume = 0.0;
+        settings.progress = 0.0;
+        settings.message = "Reset!".to_string();
+    }
+
+    let response = list(
+        &mut term,
+        GridRect::new([20, 4], [18, 15]),
+        &ITEMS,
+        &mut settings.inventory,
+        &input,
+        &style,
+    );
+    if let Some(i) = response.activated {
+        settings.message = format!("Used {}", ITEMS[i]);
+    }
+    term.put_string([20, 1].pivot(Pivot::BottomLeft), settings.message.as_str());
+}
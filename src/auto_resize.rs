@@ -0,0 +1,176 @@
+//! Automatically resizing terminals to fill a window.
+
+use bevy::{
+    app::{Plugin, PostUpdate},
+    ecs::{
+        change_detection::DetectChanges,
+        component::Component,
+        entity::Entity,
+        event::{Event, EventReader, EventWriter},
+        query::With,
+        schedule::IntoSystemConfigs,
+        system::Query,
+        world::Ref,
+    },
+    math::UVec2,
+    window::{PrimaryWindow, Window, WindowResized},
+};
+
+use crate::{
+    border::TerminalBorder,
+    transform::{TerminalSystemsUpdateTransform, TerminalTransform},
+    Terminal,
+};
+
+pub(crate) struct TerminalAutoResizePlugin;
+
+impl Plugin for TerminalAutoResizePlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_event::<TerminalResizedEvent>().add_systems(
+            PostUpdate,
+            auto_resize.before(TerminalSystemsUpdateTransform),
+        );
+    }
+}
+
+/// A component which resizes a terminal to the number of tiles that fit in
+/// a window, based on the terminal font's pixels per tile and a zoom factor.
+///
+/// The terminal will be resized any time the window is resized or the font
+/// changes, and a [TerminalResizedEvent] will be sent so the terminal contents
+/// can be redrawn. Note that resizing a terminal clears it.
+///
+/// The size is chosen so the terminal, including it's border, fits the window
+/// exactly at the given zoom. This means a [crate::TerminalCamera] using
+/// [crate::TerminalCameraScaling::Integer] will pick the same zoom and the
+/// terminal will fill the window. If the camera is framing other terminals as
+/// well consider using [crate::TerminalCameraScaling::Expand] instead.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalAutoResize {
+    /// The number of window pixels per font pixel. Values less than 1 are
+    /// treated as 1.
+    pub zoom: u32,
+    /// The window to fill. If [None] the primary window is used.
+    pub window: Option<Entity>,
+}
+
+impl Default for TerminalAutoResize {
+    fn default() -> Self {
+        Self {
+            zoom: 1,
+            window: None,
+        }
+    }
+}
+
+impl TerminalAutoResize {
+    pub fn new(zoom: u32) -> Self {
+        Self {
+            zoom,
+            ..Default::default()
+        }
+    }
+
+    /// Fill the given window rather than the primary window.
+    pub fn with_window(mut self, window: Entity) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// The terminal size that fits in a window of the given physical size.
+    pub fn fit_size(
+        &self,
+        window_size: UVec2,
+        pixels_per_tile: UVec2,
+        border: Option<&TerminalBorder>,
+    ) -> UVec2 {
+        let tile_px = pixels_per_tile.max(UVec2::ONE) * self.zoom.max(1);
+        let border_size = border.map_or(UVec2::ZERO, |b| {
+            UVec2::new(
+                b.has_left_side() as u32 + b.has_right_side() as u32,
+                b.has_top_side() as u32 + b.has_bottom_side() as u32,
+            )
+        });
+        // Terminals can't be smaller than 2x2, see [Terminal::resize]
+        (window_size / tile_px)
+            .saturating_sub(border_size)
+            .max(UVec2::splat(2))
+    }
+}
+
+/// Sent when a terminal with a [TerminalAutoResize] component is resized.
+/// Sent in [PostUpdate].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalResizedEvent {
+    pub entity: Entity,
+    /// The new size of the terminal.
+    pub size: UVec2,
+}
+
+#[allow(clippy::type_complexity)]
+fn auto_resize(
+    mut q_term: Query<(
+        Entity,
+        &mut Terminal,
+        Ref<TerminalAutoResize>,
+        Ref<TerminalTransform>,
+        Option<Ref<TerminalBorder>>,
+    )>,
+    q_window: Query<&Window>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    mut resize_events: EventReader<WindowResized>,
+    mut evt: EventWriter<TerminalResizedEvent>,
+) {
+    let window_resized = resize_events.read().count() > 0;
+    let primary_window = primary_window.get_single().ok();
+    for (entity, mut term, auto, transform, border) in &mut q_term {
+        let changed = window_resized
+            || auto.is_changed()
+            || transform.is_changed()
+            || border.as_ref().is_some_and(|b| b.is_changed());
+        if !changed {
+            continue;
+        }
+        // Font images may still be loading
+        let Some(ppu) = transform
+            .cached_data
+            .as_ref()
+            .map(|d| d.pixels_per_tile)
+            .filter(|ppu| ppu.cmpgt(UVec2::ZERO).all())
+        else {
+            continue;
+        };
+        let Some(window) = auto
+            .window
+            .or(primary_window)
+            .and_then(|w| q_window.get(w).ok())
+        else {
+            continue;
+        };
+        let window_size = UVec2::new(window.physical_width(), window.physical_height());
+        let size = auto.fit_size(window_size, ppu, border.as_deref());
+        if size != term.size() {
+            term.resize(size);
+            evt.send(TerminalResizedEvent { entity, size });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_size() {
+        let auto = TerminalAutoResize::new(2);
+        let size = auto.fit_size(UVec2::new(800, 600), UVec2::new(8, 8), None);
+        assert_eq!(UVec2::new(50, 37), size);
+
+        let border = TerminalBorder::single_line();
+        let size = auto.fit_size(UVec2::new(800, 600), UVec2::new(8, 8), Some(&border));
+        assert_eq!(UVec2::new(48, 35), size);
+
+        let size = auto.fit_size(UVec2::new(10, 10), UVec2::new(8, 8), Some(&border));
+        assert_eq!(UVec2::new(2, 2), size);
+    }
+}
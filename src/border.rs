@@ -5,7 +5,7 @@ use enum_ordinalize::Ordinalize;
 use sark_grids::{GridPoint, GridRect, GridSize, Pivot};
 
 use crate::{
-    string::{DecoratedString, StringDecoration, StringIter},
+    string::{DecoratedString, StringDecoration, StringFormatting, StringIter, TerminalString},
     Tile,
 };
 
@@ -17,9 +17,12 @@ use crate::{
 /// The border can have "empty" sides which will be ignored when building the
 /// terminal mesh.
 #[derive(Debug, Default, Clone, Component, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TerminalBorder {
     pub edge_glyphs: [Option<char>; 8],
     pub border_strings: Vec<BorderString>,
+    // Border tiles are rebuilt from the glyphs and strings by terminal systems.
+    #[cfg_attr(feature = "serde", serde(skip))]
     tiles: HashMap<IVec2, Tile>,
 }
 
@@ -188,18 +191,33 @@ impl TerminalBorder {
     /// * `offset` - Offset the string by the given number of tiles from it's
     ///   aligned position. Positive values adjust up/right, negative values adjust
     ///   down/left.
+    ///
+    /// If the string has a [crate::string::HorizontalAlignment] set for a top or
+    /// bottom edge, or a [crate::string::VerticalAlignment] set for a left or
+    /// right edge, it's used in place of `alignment` and `offset`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_ascii_terminal::{border::BorderSide, string::*, *};
+    ///
+    /// let mut border = TerminalBorder::single_line();
+    /// border.put_string(BorderSide::Top, 0.0, 1, "Title");
+    /// border.put_string(BorderSide::Bottom, 0.0, 0, "Footer".align(HorizontalAlignment::Center));
+    /// ```
     pub fn put_string<T: AsRef<str>>(
         &mut self,
         edge: BorderSide,
         alignment: f32,
         offset: i32,
-        string: impl Into<DecoratedString<T>>,
+        string: impl Into<TerminalString<T>>,
     ) {
-        let ds: DecoratedString<T> = string.into();
+        let ts: TerminalString<T> = string.into();
         let bs = BorderString {
             edge,
-            string: String::from(ds.string.as_ref()),
-            decoration: ds.decoration,
+            string: String::from(ts.string.as_ref()),
+            decoration: ts.decoration,
+            formatting: ts.formatting,
             offset,
             alignment,
         };
@@ -208,7 +226,8 @@ impl TerminalBorder {
 
     /// Write a title to the top left of the border.
     pub fn put_title<T: AsRef<str>>(&mut self, string: impl Into<DecoratedString<T>>) {
-        self.put_string(BorderSide::Top, 0.0, 0, string);
+        let ds: DecoratedString<T> = string.into();
+        self.put_string(BorderSide::Top, 0.0, 0, ds);
     }
 
     pub fn bounds(&self, size: impl GridSize) -> GridRect {
@@ -287,7 +306,12 @@ impl TerminalBorder {
                 BorderSide::Left | BorderSide::Right => IVec2::new(0, -1),
             };
             let char_count = s.string.chars().count();
+            let aligned = match s.edge {
+                BorderSide::Top | BorderSide::Bottom => s.formatting.horizontal_alignment.is_some(),
+                BorderSide::Left | BorderSide::Right => s.formatting.vertical_alignment.is_some(),
+            };
             let offset = match s.edge {
+                _ if aligned => IVec2::ZERO,
                 BorderSide::Top | BorderSide::Bottom => {
                     let align_off = ((bounds.width() - 2) as f32 * s.alignment).round() as i32;
                     let size_off = (char_count as f32 * s.alignment).round() as i32;
@@ -315,9 +339,13 @@ impl TerminalBorder {
                 }
             };
 
-            for (p, (ch, fg, bg)) in
-                StringIter::new(&s.string, side_rect, offset, None, Some(s.decoration))
-            {
+            for (p, (ch, fg, bg)) in StringIter::new(
+                &s.string,
+                side_rect,
+                offset,
+                Some(s.formatting),
+                Some(s.decoration),
+            ) {
                 // decoration.clear_colors is ignored in borders since we don't have
                 // an existing tile to work from.
                 self.tiles.insert(
@@ -339,6 +367,7 @@ impl TerminalBorder {
 
 /// One of four sides of a border.
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Ordinalize, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BorderSide {
     Top,
     Left,
@@ -347,16 +376,20 @@ pub enum BorderSide {
 }
 
 #[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BorderString {
     pub edge: BorderSide,
     pub string: String,
     pub decoration: StringDecoration,
+    pub formatting: StringFormatting,
     pub offset: i32,
     pub alignment: f32,
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::string::{HorizontalAlignment, StringFormatter};
+
     use super::*;
 
     #[test]
@@ -376,4 +409,18 @@ mod tests {
             println!("{:?} {:?}", p, t);
         }
     }
+
+    #[test]
+    fn aligned_border_string() {
+        let mut border = TerminalBorder::single_line();
+        border.put_string(
+            BorderSide::Bottom,
+            0.0,
+            0,
+            "Hi".align(HorizontalAlignment::Center),
+        );
+        border.rebuild([10, 3], Tile::default());
+        assert_eq!('H', border.tiles()[&IVec2::new(4, -1)].glyph);
+        assert_eq!('i', border.tiles()[&IVec2::new(5, -1)].glyph);
+    }
 }
@@ -0,0 +1,248 @@
+//! A compact representation of terminal tile data, used when serializing
+//! terminals and writing terminal snapshots.
+//!
+//! Tile colors are stored once in a palette and referenced by index, and
+//! consecutive identical tiles are collapsed into a single run.
+
+use bevy::{
+    color::{ColorToComponents, LinearRgba},
+    math::UVec2,
+    utils::HashMap,
+};
+
+use crate::{TerminalError, Tile};
+
+/// A run of identical tiles, with colors stored as indices into a palette.
+///
+/// Serialized as a `(count, glyph, fg, bg)` tuple.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "(u32, char, u32, u32)", into = "(u32, char, u32, u32)")
+)]
+pub(crate) struct TileRun {
+    pub count: u32,
+    pub glyph: char,
+    pub fg: u32,
+    pub bg: u32,
+}
+
+/// A sequence of tiles compressed into a color palette and runs of identical
+/// tiles.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct CompactTiles {
+    pub palette: Vec<LinearRgba>,
+    pub runs: Vec<TileRun>,
+}
+
+impl CompactTiles {
+    pub fn encode<'a>(tiles: impl IntoIterator<Item = &'a Tile>) -> Self {
+        let mut palette = Vec::new();
+        // Colors are hashed by their bits since floats can't be hashed directly.
+        let mut indices: HashMap<[u32; 4], u32> = HashMap::default();
+        let mut index_of = |color: LinearRgba| {
+            let key = color.to_f32_array().map(f32::to_bits);
+            *indices.entry(key).or_insert_with(|| {
+                palette.push(color);
+                palette.len() as u32 - 1
+            })
+        };
+
+        let mut runs: Vec<TileRun> = Vec::new();
+        for tile in tiles {
+            let fg = index_of(tile.fg_color);
+            let bg = index_of(tile.bg_color);
+            match runs.last_mut() {
+                Some(run) if run.glyph == tile.glyph && run.fg == fg && run.bg == bg => {
+                    run.count += 1;
+                }
+                _ => runs.push(TileRun {
+                    count: 1,
+                    glyph: tile.glyph,
+                    fg,
+                    bg,
+                }),
+            }
+        }
+        Self { palette, runs }
+    }
+
+    /// The total number of tiles represented by all runs. Returns an error if
+    /// the total doesn't fit in a `u32`, since run counts are untrusted.
+    pub fn tile_count(&self) -> Result<usize, TerminalError> {
+        self.runs
+            .iter()
+            .try_fold(0u32, |total, r| total.checked_add(r.count))
+            .map(|total| total as usize)
+            .ok_or_else(|| TerminalError::InvalidFormat("Too many tiles".to_string()))
+    }
+
+    /// Check that the runs cover exactly the tiles of a terminal of the given
+    /// size. Untrusted data should be checked before the terminal is allocated.
+    pub fn check_size(&self, size: UVec2) -> Result<(), TerminalError> {
+        let len = (size.x as usize)
+            .checked_mul(size.y as usize)
+            .filter(|&len| len > 0)
+            .ok_or(TerminalError::InvalidDimensions(size))?;
+        let count = self.tile_count()?;
+        if count != len {
+            return Err(TerminalError::InvalidFormat(format!(
+                "Expected {} tiles but found {}",
+                len, count
+            )));
+        }
+        Ok(())
+    }
+
+    /// Decompress the tiles into the given buffer, which must be exactly the
+    /// length of [CompactTiles::tile_count].
+    pub fn decode_into(&self, tiles: &mut [Tile]) -> Result<(), TerminalError> {
+        let count = self.tile_count()?;
+        if count != tiles.len() {
+            return Err(TerminalError::InvalidFormat(format!(
+                "Expected {} tiles but found {}",
+                tiles.len(),
+                count
+            )));
+        }
+        let color = |i: u32| {
+            self.palette.get(i as usize).copied().ok_or_else(|| {
+                TerminalError::InvalidFormat(format!("Palette index {} out of range", i))
+            })
+        };
+        let mut i = 0;
+        for run in &self.runs {
+            let tile = Tile::new(run.glyph, color(run.fg)?, color(run.bg)?);
+            let count = run.count as usize;
+            tiles[i..i + count].fill(tile);
+            i += count;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use bevy::math::UVec2;
+    use serde::{Deserialize, Serialize};
+
+    use super::{CompactTiles, TileRun};
+    use crate::{Terminal, TerminalError, Tile};
+
+    impl From<(u32, char, u32, u32)> for TileRun {
+        fn from((count, glyph, fg, bg): (u32, char, u32, u32)) -> Self {
+            Self {
+                count,
+                glyph,
+                fg,
+                bg,
+            }
+        }
+    }
+
+    impl From<TileRun> for (u32, char, u32, u32) {
+        fn from(run: TileRun) -> Self {
+            (run.count, run.glyph, run.fg, run.bg)
+        }
+    }
+
+    /// The serialized form of a [Terminal].
+    #[derive(Serialize, Deserialize)]
+    pub(crate) struct SerializedTerminal {
+        size: UVec2,
+        clear_tile: Tile,
+        tiles: CompactTiles,
+    }
+
+    impl From<Terminal> for SerializedTerminal {
+        fn from(term: Terminal) -> Self {
+            Self {
+                size: term.size(),
+                clear_tile: term.clear_tile(),
+                tiles: CompactTiles::encode(term.tiles()),
+            }
+        }
+    }
+
+    impl TryFrom<SerializedTerminal> for Terminal {
+        type Error = TerminalError;
+
+        fn try_from(value: SerializedTerminal) -> Result<Self, Self::Error> {
+            value.tiles.check_size(value.size)?;
+            let mut term = Terminal::new(value.size).with_clear_tile(value.clear_tile);
+            value.tiles.decode_into(term.tiles_mut())?;
+            Ok(term)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub(crate) use serialize::SerializedTerminal;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color;
+
+    #[test]
+    fn encode_runs() {
+        let a = Tile::new('a', color::WHITE, color::BLACK);
+        let b = Tile::new('b', color::RED, color::BLACK);
+        let tiles = [a, a, a, b, b, a];
+        let compact = CompactTiles::encode(&tiles);
+        assert_eq!(3, compact.palette.len());
+        assert_eq!(3, compact.runs.len());
+        assert_eq!(3, compact.runs[0].count);
+        assert_eq!(2, compact.runs[1].count);
+        assert_eq!(6, compact.tile_count().unwrap());
+
+        let mut decoded = [Tile::default(); 6];
+        compact.decode_into(&mut decoded).unwrap();
+        assert_eq!(tiles, decoded);
+    }
+
+    #[test]
+    fn decode_errors() {
+        let compact = CompactTiles::encode(&[Tile::default(); 4]);
+        let mut decoded = [Tile::default(); 3];
+        assert!(compact.decode_into(&mut decoded).is_err());
+
+        let mut bad_palette = compact.clone();
+        bad_palette.runs[0].fg = 10;
+        let mut decoded = [Tile::default(); 4];
+        assert!(bad_palette.decode_into(&mut decoded).is_err());
+
+        assert!(compact.check_size(UVec2::new(2, 2)).is_ok());
+        assert!(compact.check_size(UVec2::new(u32::MAX, u32::MAX)).is_err());
+
+        let mut overflow = compact.clone();
+        overflow.runs[0].count = u32::MAX;
+        overflow.runs.push(overflow.runs[0]);
+        assert!(matches!(
+            overflow.tile_count(),
+            Err(TerminalError::InvalidFormat(_))
+        ));
+        assert!(overflow.check_size(UVec2::new(u32::MAX, 2)).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_terminal() {
+        use crate::{string::StringDecorator, Terminal};
+
+        let term = Terminal::new([12, 4])
+            .with_clear_tile(Tile::new('.', color::GRAY, color::NAVY))
+            .with_string([0, 0], "Hello".fg(color::RED));
+        let ser = ron::to_string(&term).unwrap();
+        let de: Terminal = ron::from_str(&ser).unwrap();
+        assert_eq!(term.size(), de.size());
+        assert_eq!(term.clear_tile(), de.clear_tile());
+        assert_eq!(term.tiles(), de.tiles());
+
+        let huge = ser.replacen("size:(12,4)", "size:(4294967295,4294967295)", 1);
+        assert_ne!(ser, huge);
+        assert!(ron::from_str::<Terminal>(&huge).is_err());
+    }
+}
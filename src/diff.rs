@@ -0,0 +1,297 @@
+//! Computing and applying minimal differences between terminal states.
+
+use bevy::{
+    app::{Plugin, PostUpdate},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::{Event, EventWriter},
+        query::Changed,
+        schedule::IntoSystemConfigs,
+        system::Query,
+    },
+    math::UVec2,
+};
+
+use crate::{transform::TerminalSystemsUpdateTransform, Terminal, TerminalError, Tile};
+
+pub(crate) struct TerminalDiffPlugin;
+
+impl Plugin for TerminalDiffPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_event::<TerminalDiffEvent>().add_systems(
+            PostUpdate,
+            track_diffs.after(TerminalSystemsUpdateTransform),
+        );
+    }
+}
+
+/// A run of consecutive changed tiles.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiffRun {
+    /// The 1d index of the first tile in the run. See [Terminal::tile_to_index].
+    pub start: u32,
+    /// The new tile values, in order from `start`.
+    pub tiles: Vec<Tile>,
+}
+
+/// The set of tile changes required to turn one terminal state into another.
+///
+/// Changed tiles are grouped into runs of consecutive tiles. If the terminal
+/// was resized the diff will contain every tile in the new terminal.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ascii_terminal::{diff::TerminalDiff, *};
+///
+/// let a = Terminal::new([10, 2]);
+/// let b = a.clone().with_string([0, 0], "Hello");
+/// let diff = TerminalDiff::between(&a, &b);
+/// assert_eq!(5, diff.changed_tile_count());
+///
+/// let mut c = a.clone();
+/// diff.apply(&mut c).unwrap();
+/// assert_eq!(b.tiles(), c.tiles());
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TerminalDiff {
+    /// The size of the terminal the diff should be applied to.
+    pub size: UVec2,
+    pub runs: Vec<DiffRun>,
+}
+
+impl TerminalDiff {
+    /// A diff containing every tile in the terminal.
+    pub fn full(term: &Terminal) -> Self {
+        Self {
+            size: term.size(),
+            runs: vec![DiffRun {
+                start: 0,
+                tiles: term.tiles().to_vec(),
+            }],
+        }
+    }
+
+    /// Compute the diff required to turn `from` into `to`.
+    pub fn between(from: &Terminal, to: &Terminal) -> Self {
+        if from.size() != to.size() {
+            return Self::full(to);
+        }
+        let changed = from
+            .tiles()
+            .iter()
+            .zip(to.tiles())
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(i, _)| i);
+        Self::from_indices(to, changed)
+    }
+
+    /// Build a diff from the given tile indices of a terminal, for example
+    /// from an externally tracked set of changed tiles.
+    ///
+    /// Indices must be in ascending order to be grouped into runs. Out of
+    /// bounds indices are ignored.
+    pub fn from_indices(term: &Terminal, indices: impl IntoIterator<Item = usize>) -> Self {
+        let mut runs: Vec<DiffRun> = Vec::new();
+        for i in indices.into_iter().filter(|i| *i < term.tile_count()) {
+            let tile = term.tiles()[i];
+            match runs.last_mut() {
+                Some(run) if run.start as usize + run.tiles.len() == i => run.tiles.push(tile),
+                _ => runs.push(DiffRun {
+                    start: i as u32,
+                    tiles: vec![tile],
+                }),
+            }
+        }
+        Self {
+            size: term.size(),
+            runs,
+        }
+    }
+
+    /// True if the diff contains no changes.
+    pub fn is_empty(&self) -> bool {
+        self.runs.iter().all(|r| r.tiles.is_empty())
+    }
+
+    /// The total number of tiles changed by this diff.
+    pub fn changed_tile_count(&self) -> usize {
+        self.runs.iter().map(|r| r.tiles.len()).sum()
+    }
+
+    /// Apply the diff to a terminal. The terminal will be resized to match
+    /// the diff if needed.
+    ///
+    /// Returns an error if any run falls outside the bounds of the diff size,
+    /// or if the terminal needs resizing and the runs don't cover every tile
+    /// of the new size in order. In either case the terminal is left
+    /// unchanged.
+    pub fn apply(&self, term: &mut Terminal) -> Result<(), TerminalError> {
+        let tile_count = (self.size.x as usize)
+            .checked_mul(self.size.y as usize)
+            .filter(|&count| count > 0)
+            .ok_or(TerminalError::InvalidDimensions(self.size))?;
+        if let Some(run) = self
+            .runs
+            .iter()
+            .find(|r| r.start as usize + r.tiles.len() > tile_count)
+        {
+            return Err(TerminalError::InvalidFormat(format!(
+                "Diff run at index {} with {} tiles is out of bounds for size {}",
+                run.start,
+                run.tiles.len(),
+                self.size
+            )));
+        }
+        if term.size() != self.size {
+            // The size is untrusted, so only allocate once we know the runs
+            // fill the entire new terminal
+            let covered = self.runs.iter().try_fold(0, |end, r| {
+                (r.start as usize == end).then_some(end + r.tiles.len())
+            });
+            if covered != Some(tile_count) {
+                return Err(TerminalError::InvalidFormat(format!(
+                    "Diff runs must cover all {} tiles when resizing to {}",
+                    tile_count, self.size
+                )));
+            }
+            *term = Terminal::new(self.size).with_clear_tile(term.clear_tile());
+        }
+        for run in &self.runs {
+            let start = run.start as usize;
+            term.tiles_mut()[start..start + run.tiles.len()].copy_from_slice(&run.tiles);
+        }
+        Ok(())
+    }
+}
+
+/// A component which tracks changes to a terminal, sending a
+/// [TerminalDiffEvent] every time the terminal is modified.
+///
+/// The first event sent will always contain the entire terminal.
+#[derive(Component, Default, Debug, Clone)]
+pub struct TrackTerminalDiffs {
+    previous: Option<Terminal>,
+}
+
+/// An event containing the changes made to a terminal with a
+/// [TrackTerminalDiffs] component. Sent in [PostUpdate].
+#[derive(Event, Debug, Clone)]
+pub struct TerminalDiffEvent {
+    pub entity: Entity,
+    pub diff: TerminalDiff,
+}
+
+fn track_diffs(
+    mut q_term: Query<(Entity, &Terminal, &mut TrackTerminalDiffs), Changed<Terminal>>,
+    mut evt: EventWriter<TerminalDiffEvent>,
+) {
+    for (entity, term, mut tracker) in &mut q_term {
+        let diff = match &tracker.previous {
+            Some(prev) => TerminalDiff::between(prev, term),
+            None => TerminalDiff::full(term),
+        };
+        if diff.is_empty() {
+            continue;
+        }
+        match &mut tracker.previous {
+            Some(prev) if prev.size() == term.size() => {
+                prev.tiles_mut().copy_from_slice(term.tiles())
+            }
+            prev => *prev = Some(term.clone()),
+        }
+        evt.send(TerminalDiffEvent { entity, diff });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color, string::StringDecorator};
+
+    #[test]
+    fn diff_runs() {
+        let a = Terminal::new([10, 3]);
+        let mut b = a.clone();
+        b.put_char([0, 0], 'a');
+        b.put_char([1, 0], 'b');
+        b.put_char([5, 0], 'c');
+        b.put_fg_color([9, 2], color::RED);
+        let diff = TerminalDiff::between(&a, &b);
+        assert_eq!(3, diff.runs.len());
+        assert_eq!(4, diff.changed_tile_count());
+        assert_eq!(0, diff.runs[0].start);
+        assert_eq!(2, diff.runs[0].tiles.len());
+        assert_eq!(29, diff.runs[2].start);
+
+        let mut c = a.clone();
+        diff.apply(&mut c).unwrap();
+        assert_eq!(b.tiles(), c.tiles());
+        assert!(TerminalDiff::between(&b, &c).is_empty());
+    }
+
+    #[test]
+    fn diff_resized() {
+        let a = Terminal::new([4, 4]);
+        let b = Terminal::new([8, 2]).with_string([0, 0], "Resized".fg(color::BLUE));
+        let diff = TerminalDiff::between(&a, &b);
+        assert_eq!(16, diff.changed_tile_count());
+
+        let mut c = a.clone();
+        diff.apply(&mut c).unwrap();
+        assert_eq!(b.size(), c.size());
+        assert_eq!(b.tiles(), c.tiles());
+    }
+
+    #[test]
+    fn apply_out_of_bounds() {
+        let mut term = Terminal::new([4, 4]);
+        let diff = TerminalDiff {
+            size: term.size(),
+            runs: vec![DiffRun {
+                start: 14,
+                tiles: vec![Tile::default(); 3],
+            }],
+        };
+        assert!(diff.apply(&mut term).is_err());
+    }
+
+    #[test]
+    fn apply_overflowing_size() {
+        let mut term = Terminal::new([4, 4]);
+        let diff = TerminalDiff {
+            size: UVec2::new(u32::MAX, u32::MAX),
+            runs: Vec::new(),
+        };
+        assert!(diff.apply(&mut term).is_err());
+        assert_eq!(UVec2::new(4, 4), term.size());
+
+        let diff = TerminalDiff {
+            size: UVec2::new(65535, 65535),
+            runs: Vec::new(),
+        };
+        assert!(diff.apply(&mut term).is_err());
+        assert_eq!(UVec2::new(4, 4), term.size());
+    }
+
+    #[test]
+    fn apply_partial_resize() {
+        let mut term = Terminal::new([4, 4]);
+        let diff = TerminalDiff {
+            size: UVec2::new(2, 2),
+            runs: vec![DiffRun {
+                start: 1,
+                tiles: vec![Tile::default(); 3],
+            }],
+        };
+        assert!(matches!(
+            diff.apply(&mut term),
+            Err(TerminalError::InvalidFormat(_))
+        ));
+        assert_eq!(UVec2::new(4, 4), term.size());
+    }
+}
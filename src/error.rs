@@ -0,0 +1,26 @@
+//! Errors that can occur when building or loading terminals.
+
+use bevy::math::UVec2;
+use thiserror::Error;
+
+/// An error that can occur when creating a terminal from external data, such
+/// as a file, an asset or a string.
+#[derive(Error, Debug)]
+pub enum TerminalError {
+    /// An error occurred while reading the underlying data.
+    #[error("Error reading terminal data: {0}")]
+    Io(#[from] std::io::Error),
+    /// The data was read successfully but was not in the expected format.
+    #[error("Invalid terminal data format: {0}")]
+    InvalidFormat(String),
+    /// A glyph index could not be converted into a terminal glyph.
+    #[error("Unsupported glyph index {0}")]
+    UnsupportedGlyph(u32),
+    /// The terminal size is invalid, typically because it would have no tiles.
+    #[error("Invalid terminal dimensions {0}")]
+    InvalidDimensions(UVec2),
+    /// Multiple layers of terminal data with different sizes were found where
+    /// they were expected to be the same size.
+    #[error("Mismatched layer size, expected {expected} but found {found}")]
+    MismatchedLayerSizes { expected: UVec2, found: UVec2 },
+}
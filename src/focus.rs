@@ -0,0 +1,117 @@
+//! Keyboard and mouse focus shared between terminals.
+
+use bevy::{
+    app::{Plugin, PreUpdate},
+    ecs::{
+        entity::Entity,
+        query::With,
+        schedule::IntoSystemConfigs,
+        system::{Query, ResMut, Resource},
+    },
+};
+
+use crate::{mouse::TerminalSystemsMouseInput, Terminal};
+
+pub(crate) struct TerminalFocusPlugin;
+
+impl Plugin for TerminalFocusPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<TerminalFocus>().add_systems(
+            PreUpdate,
+            remove_despawned.before(TerminalSystemsMouseInput),
+        );
+    }
+}
+
+/// A stack of terminal entities which have captured input focus.
+///
+/// While the stack is empty every terminal receives input as normal. When a
+/// terminal is pushed onto the stack, for example by opening a
+/// [crate::widget::Popup], only the terminal on top of the stack receives
+/// [crate::TerminalMouseEvent]s and keyboard input for built in widgets.
+/// Removing it returns focus to the terminal below it.
+///
+/// Despawned terminals are removed from the stack automatically.
+///
+/// # Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_ascii_terminal::*;
+///
+/// fn handle_keys(q_term: Query<Entity, With<Terminal>>, focus: Res<TerminalFocus>) {
+///     for entity in &q_term {
+///         if !focus.has_focus(entity) {
+///             continue;
+///         }
+///         // Handle keyboard input for this terminal
+///     }
+/// }
+/// ```
+#[derive(Resource, Debug, Default, Clone)]
+pub struct TerminalFocus {
+    stack: Vec<Entity>,
+}
+
+impl TerminalFocus {
+    /// Give focus to a terminal, moving it to the top of the stack if it's
+    /// already on it.
+    pub fn push(&mut self, entity: Entity) {
+        self.remove(entity);
+        self.stack.push(entity);
+    }
+
+    /// Remove a terminal from the stack, returning focus to the terminal below
+    /// it if it was on top.
+    pub fn remove(&mut self, entity: Entity) {
+        self.stack.retain(|e| *e != entity);
+    }
+
+    /// The terminal on top of the stack.
+    pub fn focused(&self) -> Option<Entity> {
+        self.stack.last().copied()
+    }
+
+    /// True if the terminal should receive input. This is true for every
+    /// terminal while the stack is empty.
+    pub fn has_focus(&self, entity: Entity) -> bool {
+        self.focused().is_none_or(|e| e == entity)
+    }
+
+    /// The focus stack, from bottom to top.
+    pub fn stack(&self) -> &[Entity] {
+        &self.stack
+    }
+
+    /// Remove every terminal from the stack, giving focus back to all
+    /// terminals.
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+}
+
+fn remove_despawned(mut focus: ResMut<TerminalFocus>, q_term: Query<(), With<Terminal>>) {
+    if focus.stack.iter().any(|e| !q_term.contains(*e)) {
+        focus.stack.retain(|e| q_term.contains(*e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stack() {
+        let [a, b] = [Entity::from_raw(1), Entity::from_raw(2)];
+        let mut focus = TerminalFocus::default();
+        assert!(focus.has_focus(a) && focus.has_focus(b));
+        focus.push(a);
+        focus.push(b);
+        assert!(!focus.has_focus(a));
+        assert!(focus.has_focus(b));
+        focus.push(a);
+        assert_eq!(&[b, a], focus.stack());
+        focus.remove(a);
+        assert_eq!(Some(b), focus.focused());
+    }
+}
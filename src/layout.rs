@@ -0,0 +1,435 @@
+//! Dividing a terminal into panels.
+//!
+//! A [Layout] describes a screen as nested rows and columns of [Panel]s, each
+//! with a [Constraint] on it's size. Computing the layout for a [GridRect]
+//! returns the rect of every panel, so the same layout adapts to any terminal
+//! size. Panels can optionally be framed by a [TerminalBorder], which is drawn
+//! into the terminal by [Layout::draw].
+//!
+//! # Example
+//!
+//! ```
+//! use bevy_ascii_terminal::{layout::*, *};
+//!
+//! let layout = Layout::columns()
+//!     .with(Panel::flex(1).with_name("map").with_title("Map"))
+//!     .with(
+//!         Panel::fixed(20).with_layout(
+//!             Layout::rows()
+//!                 .with(Panel::percent(50).with_name("stats").with_title("Stats"))
+//!                 .with(Panel::flex(1).with_name("log").with_title("Log")),
+//!         ),
+//!     );
+//!
+//! let mut term = Terminal::new([60, 30]);
+//! let rects = layout.draw(&mut term);
+//! assert_eq!([38, 28], rects.get("map").unwrap().size.to_array());
+//! assert_eq!([18, 13], rects.get("stats").unwrap().size.to_array());
+//! ```
+
+use bevy::math::{IVec2, UVec2};
+use sark_grids::GridRect;
+
+use crate::{Terminal, TerminalBorder};
+
+/// How the size of a [Panel] is determined along the direction of it's
+/// parent [Layout].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constraint {
+    /// A fixed number of tiles.
+    Fixed(usize),
+    /// A percentage of the available space, from 0 to 100.
+    Percent(u32),
+    /// A share of the space left over after fixed and percentage panels,
+    /// proportional to it's weight relative to other flex panels.
+    Flex(u32),
+}
+
+/// The direction a [Layout] arranges it's panels in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutDirection {
+    /// Panels are placed side by side, from left to right.
+    #[default]
+    Horizontal,
+    /// Panels are stacked from top to bottom.
+    Vertical,
+}
+
+/// A row or column of [Panel]s.
+#[derive(Debug, Clone, Default)]
+pub struct Layout {
+    pub direction: LayoutDirection,
+    /// The number of empty tiles between each panel.
+    pub spacing: usize,
+    pub panels: Vec<Panel>,
+}
+
+/// A single panel in a [Layout].
+#[derive(Debug, Clone)]
+pub struct Panel {
+    pub size: Constraint,
+    /// An optional name used to look up the panel's rect in [LayoutRects].
+    pub name: Option<String>,
+    /// An optional border drawn around the inside edge of the panel.
+    pub border: Option<TerminalBorder>,
+    /// A nested layout dividing the inside of the panel.
+    pub layout: Option<Layout>,
+}
+
+/// The computed rect of a [Panel].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanelRect {
+    pub name: Option<String>,
+    /// The full area of the panel, including it's border.
+    pub outer: GridRect,
+    /// The area inside the panel's border. This is the same as `outer` if the
+    /// panel has no border.
+    pub inner: GridRect,
+}
+
+/// The result of computing a [Layout].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayoutRects {
+    /// Every panel in the layout, in depth first order.
+    pub panels: Vec<PanelRect>,
+}
+
+impl LayoutRects {
+    /// The inner rect of the first panel with the given name.
+    pub fn get(&self, name: &str) -> Option<GridRect> {
+        self.panel(name).map(|p| p.inner)
+    }
+
+    /// The computed rects of the first panel with the given name.
+    pub fn panel(&self, name: &str) -> Option<&PanelRect> {
+        self.panels.iter().find(|p| p.name.as_deref() == Some(name))
+    }
+}
+
+impl Layout {
+    pub fn new(direction: LayoutDirection) -> Self {
+        Self {
+            direction,
+            ..Default::default()
+        }
+    }
+
+    /// A layout which places it's panels side by side, from left to right.
+    pub fn columns() -> Self {
+        Self::new(LayoutDirection::Horizontal)
+    }
+
+    /// A layout which stacks it's panels from top to bottom.
+    pub fn rows() -> Self {
+        Self::new(LayoutDirection::Vertical)
+    }
+
+    /// Add a panel to the layout.
+    pub fn with(mut self, panel: Panel) -> Self {
+        self.panels.push(panel);
+        self
+    }
+
+    pub fn with_spacing(mut self, spacing: usize) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Split a rect into the outer rects of this layout's panels, ignoring
+    /// any nested layouts.
+    ///
+    /// If fixed and percentage panels don't fit they are shrunk from the
+    /// last panel backwards. Panels may have a size of zero. The spacing is
+    /// reduced if the gaps alone wouldn't fit in the rect.
+    pub fn split(&self, rect: GridRect) -> Vec<GridRect> {
+        let constraints: Vec<_> = self.panels.iter().map(|p| p.size).collect();
+        let horizontal = self.direction == LayoutDirection::Horizontal;
+        let length = if horizontal {
+            rect.width()
+        } else {
+            rect.height()
+        };
+        let spacing = match constraints.len().saturating_sub(1) {
+            0 => 0,
+            gaps => self.spacing.min(length / gaps),
+        };
+        let sizes = resolve(&constraints, length, spacing);
+        let mut offset = 0;
+        sizes
+            .into_iter()
+            .map(|size| {
+                let r = if horizontal {
+                    GridRect::new(
+                        [rect.left() + offset as i32, rect.bottom()],
+                        [size, rect.height()],
+                    )
+                } else {
+                    let top = rect.top() - offset as i32;
+                    GridRect::new([rect.left(), top + 1 - size as i32], [rect.width(), size])
+                };
+                offset += size + spacing;
+                r
+            })
+            .collect()
+    }
+
+    /// Compute the rects of every panel in the layout, including nested
+    /// layouts.
+    pub fn compute(&self, rect: GridRect) -> LayoutRects {
+        let mut rects = LayoutRects::default();
+        self.compute_into(rect, &mut rects);
+        rects
+    }
+
+    fn compute_into(&self, rect: GridRect, rects: &mut LayoutRects) {
+        for (panel, outer) in self.panels.iter().zip(self.split(rect)) {
+            let inner = panel.inner_rect(outer);
+            rects.panels.push(PanelRect {
+                name: panel.name.clone(),
+                outer,
+                inner,
+            });
+            if let Some(layout) = &panel.layout {
+                layout.compute_into(inner, rects);
+            }
+        }
+    }
+
+    /// Compute the layout for the entire terminal and draw the borders of
+    /// any framed panels.
+    ///
+    /// Since the layout is computed from the terminal's current size this can
+    /// be called again after [Terminal::resize] to redraw the layout.
+    pub fn draw(&self, term: &mut Terminal) -> LayoutRects {
+        let rects = self.compute(term.bounds());
+        self.draw_borders(term, &rects.panels);
+        rects
+    }
+
+    /// Draw the borders of this layout's panels given their computed rects.
+    /// Returns the number of panel rects consumed.
+    fn draw_borders(&self, term: &mut Terminal, rects: &[PanelRect]) -> usize {
+        let mut i = 0;
+        for panel in &self.panels {
+            let Some(rect) = rects.get(i) else {
+                break;
+            };
+            i += 1;
+            if let Some(border) = &panel.border {
+                put_border(term, rect.inner, border);
+            }
+            if let Some(layout) = &panel.layout {
+                i += layout.draw_borders(term, &rects[i..]);
+            }
+        }
+        i
+    }
+}
+
+impl Panel {
+    pub fn new(size: Constraint) -> Self {
+        Self {
+            size,
+            name: None,
+            border: None,
+            layout: None,
+        }
+    }
+
+    /// A panel with a fixed size in tiles.
+    pub fn fixed(size: usize) -> Self {
+        Self::new(Constraint::Fixed(size))
+    }
+
+    /// A panel sized as a percentage of the available space.
+    pub fn percent(percent: u32) -> Self {
+        Self::new(Constraint::Percent(percent))
+    }
+
+    /// A panel which shares the remaining space with other flex panels.
+    pub fn flex(weight: u32) -> Self {
+        Self::new(Constraint::Flex(weight))
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_border(mut self, border: TerminalBorder) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    /// Add a title to the panel's border. If the panel doesn't have a border
+    /// a [TerminalBorder::single_line] border is added.
+    pub fn with_title(mut self, title: impl AsRef<str>) -> Self {
+        let border = self
+            .border
+            .take()
+            .unwrap_or_else(TerminalBorder::single_line);
+        self.border = Some(border.with_title(title));
+        self
+    }
+
+    /// Divide the inside of the panel with a nested layout.
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
+    /// The area inside the panel's border.
+    fn inner_rect(&self, outer: GridRect) -> GridRect {
+        let Some(border) = &self.border else {
+            return outer;
+        };
+        let left = border.has_left_side() as u32;
+        let right = border.has_right_side() as u32;
+        let top = border.has_top_side() as u32;
+        let bottom = border.has_bottom_side() as u32;
+        GridRect::new(
+            outer.pos + IVec2::new(left as i32, bottom as i32),
+            [
+                outer.size.x.saturating_sub(left + right),
+                outer.size.y.saturating_sub(top + bottom),
+            ],
+        )
+    }
+}
+
+/// Draw a border around the outside of a rect, clipped to the terminal bounds.
+fn put_border(term: &mut Terminal, inner: GridRect, border: &TerminalBorder) {
+    if inner.size.cmpeq(UVec2::ZERO).any() {
+        return;
+    }
+    let mut border = border.clone();
+    border.rebuild(inner.size, term.clear_tile());
+    let bounds = term.bounds();
+    for (p, tile) in border.tiles() {
+        let p = *p + inner.pos;
+        if bounds.contains_point(p) {
+            term.put_tile(p, *tile);
+        }
+    }
+}
+
+/// Resolve constraints to sizes along an axis of the given length.
+fn resolve(constraints: &[Constraint], length: usize, spacing: usize) -> Vec<usize> {
+    let gaps = spacing * constraints.len().saturating_sub(1);
+    let available = length.saturating_sub(gaps);
+    let mut remaining = available;
+    let mut sizes: Vec<usize> = constraints
+        .iter()
+        .map(|c| {
+            let size = match *c {
+                Constraint::Fixed(n) => n,
+                Constraint::Percent(p) => available * p.min(100) as usize / 100,
+                Constraint::Flex(_) => 0,
+            };
+            let size = size.min(remaining);
+            remaining -= size;
+            size
+        })
+        .collect();
+
+    let total_weight: usize = constraints
+        .iter()
+        .map(|c| match c {
+            Constraint::Flex(w) => *w as usize,
+            _ => 0,
+        })
+        .sum();
+    if total_weight == 0 {
+        return sizes;
+    }
+    let flex_space = remaining;
+    for (size, c) in sizes.iter_mut().zip(constraints) {
+        if let Constraint::Flex(w) = c {
+            *size = flex_space * *w as usize / total_weight;
+            remaining -= *size;
+        }
+    }
+    // Hand out tiles lost to rounding, one each, from the first flex panel
+    for (size, c) in sizes.iter_mut().zip(constraints) {
+        if remaining == 0 {
+            break;
+        }
+        if matches!(c, Constraint::Flex(w) if *w > 0) {
+            *size += 1;
+            remaining -= 1;
+        }
+    }
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_constraints() {
+        use Constraint::*;
+        assert_eq!(
+            vec![10, 20, 70],
+            resolve(&[Fixed(10), Percent(20), Flex(1)], 100, 0)
+        );
+        assert_eq!(vec![4, 3, 3], resolve(&[Flex(1), Flex(1), Flex(1)], 10, 0));
+        assert_eq!(vec![3, 6], resolve(&[Flex(1), Flex(2)], 10, 1));
+        // Fixed panels that don't fit are shrunk from the end
+        assert_eq!(
+            vec![8, 2, 0],
+            resolve(&[Fixed(8), Fixed(8), Flex(1)], 10, 0)
+        );
+    }
+
+    #[test]
+    fn split_rows() {
+        let layout = Layout::rows().with(Panel::fixed(3)).with(Panel::flex(1));
+        let rects = layout.split(GridRect::new([0, 0], [10, 10]));
+        assert_eq!(GridRect::new([0, 7], [10, 3]), rects[0]);
+        assert_eq!(GridRect::new([0, 0], [10, 7]), rects[1]);
+    }
+
+    #[test]
+    fn split_large_spacing() {
+        let rect = GridRect::new([2, 3], [10, 6]);
+        let panels = Layout::columns()
+            .with(Panel::fixed(2))
+            .with(Panel::flex(1))
+            .with(Panel::fixed(2));
+        for layout in [
+            panels.clone().with_spacing(8),
+            Layout {
+                direction: LayoutDirection::Vertical,
+                ..panels.with_spacing(100)
+            },
+        ] {
+            for r in layout.split(rect) {
+                assert!(r.left() >= rect.left() && r.left() + r.width() as i32 <= rect.left() + 10);
+                assert!(
+                    r.bottom() >= rect.bottom()
+                        && r.bottom() + r.height() as i32 <= rect.bottom() + 6
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn nested_borders() {
+        let layout = Layout::columns()
+            .with(Panel::fixed(5).with_title("A"))
+            .with(
+                Panel::flex(1)
+                    .with_name("right")
+                    .with_border(TerminalBorder::single_line()),
+            );
+        let mut term = Terminal::new([12, 4]);
+        let rects = layout.draw(&mut term);
+        assert_eq!(Some(GridRect::new([6, 1], [5, 2])), rects.get("right"));
+        assert_eq!('┌', term.tile([0, 3]).glyph);
+        assert_eq!('A', term.tile([1, 3]).glyph);
+        assert_eq!('┘', term.tile([4, 0]).glyph);
+        assert_eq!('┌', term.tile([5, 3]).glyph);
+        assert_eq!('┘', term.tile([11, 0]).glyph);
+    }
+}
@@ -1,21 +1,41 @@
 pub mod ascii;
+pub mod auto_resize;
 pub mod border;
 //pub mod grid;
 pub mod color;
+pub(crate) mod compact;
+pub mod diff;
+pub mod error;
+pub mod focus;
+pub mod layout;
+pub mod mouse;
+#[cfg(feature = "picking")]
+pub mod picking;
 pub mod render;
-pub(crate) mod rexpaint;
+pub mod rexpaint;
+pub mod snapshot;
 pub mod string;
 pub mod terminal;
 pub mod tile;
 pub mod transform;
+#[cfg(feature = "ui")]
+pub mod ui;
+pub mod widget;
 
 pub use ascii::Glyph;
+pub use auto_resize::{TerminalAutoResize, TerminalResizedEvent};
 use bevy::{
     app::{Plugin, PostUpdate},
     prelude::IntoSystemSetConfigs,
 };
 pub use border::TerminalBorder;
-pub use render::{TerminalCamera, TerminalFont, TerminalMeshPivot, TerminalMeshWorldScaling};
+pub use error::TerminalError;
+pub use focus::TerminalFocus;
+pub use mouse::{TerminalMouse, TerminalMouseEvent, TerminalMouseEventKind};
+pub use render::{
+    FollowTarget, TerminalCamera, TerminalCameraFollow, TerminalCameraScaling, TerminalFont,
+    TerminalMeshPivot, TerminalMeshWorldScaling,
+};
 pub use sark_grids::{GridPoint, GridRect, GridSize, Pivot};
 pub use string::StringDecorator;
 pub use terminal::Terminal;
@@ -32,10 +52,21 @@ impl Plugin for TerminalPlugins {
             transform::TerminalTransformPlugin, // 'PostUpdate' systems
             render::TerminalUvMappingPlugin,
             render::TerminalMaterialPlugin,
-            render::TerminalFontPlugin,   // 'PostUpdate' systems
-            render::TerminalCameraPlugin, // 'First` systems
-            render::TerminalMeshPlugin,   // 'PostUpdate' systems
+            render::TerminalFontPlugin,            // 'PostUpdate' systems
+            render::TerminalCameraPlugin,          // 'First` systems
+            render::TerminalMeshPlugin,            // 'PostUpdate' systems
+            rexpaint::TerminalXpAssetPlugin,       // 'PostUpdate' systems
+            snapshot::TerminalSnapshotPlugin,      // 'PostUpdate' systems
+            diff::TerminalDiffPlugin,              // 'PostUpdate' systems
+            mouse::TerminalMousePlugin,            // 'PreUpdate' systems
+            focus::TerminalFocusPlugin,            // 'PreUpdate' systems
+            auto_resize::TerminalAutoResizePlugin, // 'PostUpdate' systems
+            widget::TerminalWidgetPlugin,          // 'PreUpdate' and 'PostUpdate' systems
         ));
+        #[cfg(feature = "picking")]
+        app.add_plugins(picking::TerminalPickingPlugin); // 'PreUpdate' systems
+        #[cfg(feature = "ui")]
+        app.add_plugins(ui::TerminalUiPlugin); // 'PreUpdate' and 'PostUpdate' systems
         app.configure_sets(
             PostUpdate,
             TerminalSystemsUpdateTransform.before(render::TerminalSystemsUpdateMesh),
@@ -0,0 +1,418 @@
+//! Tile-level mouse events for terminals.
+
+use bevy::{
+    app::{Plugin, PreUpdate},
+    ecs::{
+        entity::Entity,
+        event::{Event, EventReader, EventWriter},
+        schedule::{IntoSystemConfigs, SystemSet},
+        system::{Query, Res, ResMut, Resource},
+    },
+    input::{
+        mouse::{MouseButton, MouseScrollUnit, MouseWheel},
+        ButtonInput, InputSystem,
+    },
+    math::{IVec2, Vec2},
+    prelude::GlobalTransform,
+    render::view::RenderLayers,
+    time::Time,
+    utils::HashMap,
+};
+
+use crate::{
+    render::shares_render_layer, transform::TerminalTransform, TerminalCamera, TerminalFocus,
+};
+
+pub(crate) struct TerminalMousePlugin;
+
+/// The number of pixels treated as a single line when scrolling with a
+/// touchpad or other pixel based scrolling device.
+const PIXELS_PER_LINE: f32 = 20.0;
+
+/// System for sending [TerminalMouseEvent]s. Runs in [PreUpdate].
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, SystemSet)]
+pub struct TerminalSystemsMouseInput;
+
+impl Plugin for TerminalMousePlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_event::<TerminalMouseEvent>()
+            .init_resource::<TerminalMouse>()
+            .add_systems(
+                PreUpdate,
+                update_mouse
+                    .in_set(TerminalSystemsMouseInput)
+                    .after(InputSystem),
+            );
+    }
+}
+
+/// A mouse event that occurred on a terminal tile.
+///
+/// When terminals overlap, events are only sent to the terminal on the highest
+/// layer. See [crate::SetTerminalLayerPosition].
+///
+/// Note cursor positions are read from the [TerminalCamera], so these events
+/// won't be sent unless a [TerminalCamera] with `track_cursor` enabled exists.
+/// When there are multiple cameras, the camera with the cursor inside it's
+/// viewport is used, and only terminals sharing a render layer with that camera
+/// will receive events.
+///
+/// Terminals displayed in a `TerminalUiNode` receive events from the cursor
+/// position over their node instead, and are treated as being above any
+/// terminals in the world.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct TerminalMouseEvent {
+    /// The terminal entity the event occurred on.
+    pub entity: Entity,
+    /// The local tile position of the event.
+    ///
+    /// For drag events this may be outside the bounds of the terminal if the
+    /// cursor is dragged past the terminal's edge.
+    pub tile: IVec2,
+    pub kind: TerminalMouseEventKind,
+}
+
+/// The type of a [TerminalMouseEvent].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TerminalMouseEventKind {
+    /// The cursor entered a tile.
+    Enter,
+    /// The cursor left a tile.
+    Leave,
+    /// A mouse button was pressed over a tile.
+    Press(MouseButton),
+    /// A mouse button was released over a tile.
+    Release(MouseButton),
+    /// A mouse button was pressed and released over the same tile without
+    /// dragging.
+    Click(MouseButton),
+    /// A second click on the same tile within [TerminalMouse::double_click_time]
+    /// seconds. This is sent after the second [TerminalMouseEventKind::Click].
+    DoubleClick(MouseButton),
+    /// The cursor moved to a different tile while a mouse button was held
+    /// down. The event tile is the tile where the button was pressed.
+    DragStart(MouseButton),
+    /// The cursor moved to a different tile during a drag.
+    DragMove(MouseButton),
+    /// The mouse button was released after dragging. This is sent to the
+    /// terminal where the drag started.
+    DragEnd(MouseButton),
+    /// The mouse wheel was scrolled over a tile. The delta is measured in
+    /// lines, pixel deltas from touchpads are converted to lines.
+    Wheel(Vec2),
+}
+
+/// A resource tracking the state of the mouse relative to terminal tiles.
+#[derive(Resource, Debug, Clone)]
+pub struct TerminalMouse {
+    /// The maximum number of seconds between two clicks for them to count
+    /// as a double click.
+    pub double_click_time: f32,
+    hovered: Option<(Entity, IVec2)>,
+    presses: HashMap<MouseButton, PressState>,
+    last_click: Option<(Entity, IVec2, MouseButton, f32)>,
+    /// The cursor position mapped into world space for each terminal displayed
+    /// in a ui node, or [None] if the cursor isn't over the node.
+    pub(crate) ui_cursors: HashMap<Entity, Option<Vec2>>,
+}
+
+impl Default for TerminalMouse {
+    fn default() -> Self {
+        Self {
+            double_click_time: 0.3,
+            hovered: None,
+            presses: HashMap::default(),
+            last_click: None,
+            ui_cursors: HashMap::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PressState {
+    entity: Entity,
+    tile: IVec2,
+    last_tile: IVec2,
+    dragging: bool,
+}
+
+impl TerminalMouse {
+    /// The terminal entity and tile currently under the cursor, if any.
+    pub fn hovered(&self) -> Option<(Entity, IVec2)> {
+        self.hovered
+    }
+
+    /// The terminal entity and tile where the given mouse button was pressed,
+    /// if it's currently held down.
+    pub fn pressed(&self, button: MouseButton) -> Option<(Entity, IVec2)> {
+        self.presses.get(&button).map(|p| (p.entity, p.tile))
+    }
+
+    /// True if the given mouse button is currently being dragged.
+    pub fn is_dragging(&self, button: MouseButton) -> bool {
+        self.presses.get(&button).is_some_and(|p| p.dragging)
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn update_mouse(
+    q_cam: Query<(&TerminalCamera, Option<&RenderLayers>)>,
+    q_term: Query<(
+        Entity,
+        &TerminalTransform,
+        &GlobalTransform,
+        Option<&RenderLayers>,
+    )>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut wheel: EventReader<MouseWheel>,
+    time: Res<Time>,
+    focus: Res<TerminalFocus>,
+    mut mouse: ResMut<TerminalMouse>,
+    mut evt: EventWriter<TerminalMouseEvent>,
+) {
+    let cursor = q_cam
+        .iter()
+        .find_map(|(cam, layers)| cam.cursor_world_pos().map(|pos| (pos, layers)));
+    let (cursor, cam_layers) = cursor.unzip();
+
+    let mouse = &mut *mouse;
+    // Terminals in ui nodes are drawn over the world so they're checked first
+    let ui_hovered = mouse
+        .ui_cursors
+        .iter()
+        .filter(|(e, _)| focus.has_focus(**e))
+        .find_map(|(e, cursor)| {
+            let (_, transform, ..) = q_term.get(*e).ok()?;
+            transform.world_to_tile((*cursor)?).map(|p| (*e, p))
+        });
+    // Find the top-most terminal tile under the cursor. Terminals without
+    // focus are ignored while another terminal has captured it
+    let over_ui = mouse.ui_cursors.values().any(Option::is_some);
+    let hovered = ui_hovered.or_else(|| {
+        let cursor = cursor.filter(|_| !over_ui)?;
+        q_term
+            .iter()
+            .filter(|(e, ..)| focus.has_focus(*e))
+            .filter(|(.., layers)| shares_render_layer(cam_layers.flatten(), *layers))
+            .filter_map(|(e, t, gt, _)| t.world_to_tile(cursor).map(|p| (e, p, gt.translation().z)))
+            .max_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(e, p, _)| (e, p))
+    });
+
+    let mut send = |(entity, tile): (Entity, IVec2), kind| {
+        evt.send(TerminalMouseEvent { entity, tile, kind });
+    };
+
+    if hovered != mouse.hovered {
+        if let Some(prev) = mouse.hovered {
+            send(prev, TerminalMouseEventKind::Leave);
+        }
+        if let Some(curr) = hovered {
+            send(curr, TerminalMouseEventKind::Enter);
+        }
+        mouse.hovered = hovered;
+    }
+
+    for button in buttons.get_just_pressed() {
+        if let Some(curr) = hovered {
+            send(curr, TerminalMouseEventKind::Press(*button));
+            mouse.presses.insert(
+                *button,
+                PressState {
+                    entity: curr.0,
+                    tile: curr.1,
+                    last_tile: curr.1,
+                    dragging: false,
+                },
+            );
+        }
+    }
+
+    // Drag events are always sent to the terminal where the drag started
+    for (button, press) in mouse.presses.iter_mut() {
+        let cursor = match mouse.ui_cursors.get(&press.entity) {
+            Some(ui_cursor) => *ui_cursor,
+            None => cursor,
+        };
+        let Some(tile) = cursor.and_then(|cursor| {
+            let (_, transform, ..) = q_term.get(press.entity).ok()?;
+            transform.world_to_tile_unbounded(cursor)
+        }) else {
+            continue;
+        };
+        if tile == press.last_tile {
+            continue;
+        }
+        if !press.dragging {
+            press.dragging = true;
+            send(
+                (press.entity, press.tile),
+                TerminalMouseEventKind::DragStart(*button),
+            );
+        }
+        press.last_tile = tile;
+        send(
+            (press.entity, tile),
+            TerminalMouseEventKind::DragMove(*button),
+        );
+    }
+
+    let now = time.elapsed_secs();
+    for button in buttons.get_just_released() {
+        if let Some(curr) = hovered {
+            send(curr, TerminalMouseEventKind::Release(*button));
+        }
+        let Some(press) = mouse.presses.remove(button) else {
+            continue;
+        };
+        if press.dragging {
+            send(
+                (press.entity, press.last_tile),
+                TerminalMouseEventKind::DragEnd(*button),
+            );
+            continue;
+        }
+        if hovered != Some((press.entity, press.tile)) {
+            continue;
+        }
+        send(
+            (press.entity, press.tile),
+            TerminalMouseEventKind::Click(*button),
+        );
+        let double = mouse.last_click.is_some_and(|(e, p, b, t)| {
+            e == press.entity
+                && p == press.tile
+                && b == *button
+                && now - t <= mouse.double_click_time
+        });
+        if double {
+            send(
+                (press.entity, press.tile),
+                TerminalMouseEventKind::DoubleClick(*button),
+            );
+            mouse.last_click = None;
+        } else {
+            mouse.last_click = Some((press.entity, press.tile, *button, now));
+        }
+    }
+
+    let scroll = wheel.read().fold(Vec2::ZERO, |acc, w| {
+        let delta = Vec2::new(w.x, w.y);
+        match w.unit {
+            MouseScrollUnit::Line => acc + delta,
+            MouseScrollUnit::Pixel => acc + delta / PIXELS_PER_LINE,
+        }
+    });
+    if scroll != Vec2::ZERO {
+        if let Some(curr) = hovered {
+            send(curr, TerminalMouseEventKind::Wheel(scroll));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        app::App,
+        ecs::{entity::Entity, event::Events},
+        input::{
+            mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel},
+            ButtonState, InputPlugin,
+        },
+        math::{IVec2, Rect, UVec2, Vec2, Vec3},
+        prelude::GlobalTransform,
+        MinimalPlugins,
+    };
+
+    use super::*;
+    use crate::transform::CachedTransformData;
+
+    fn setup() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin, TerminalMousePlugin))
+            .init_resource::<TerminalFocus>();
+        let transform = TerminalTransform {
+            cached_data: Some(CachedTransformData {
+                world_tile_size: Vec2::ONE,
+                terminal_size: UVec2::new(10, 5),
+                local_inner_mesh_bounds: Rect::new(0.0, 0.0, 10.0, 5.0),
+                world_mesh_bounds: Rect::new(0.0, 0.0, 10.0, 5.0),
+                world_pos: Vec3::ZERO,
+                pixels_per_tile: UVec2::splat(8),
+            }),
+        };
+        let term = app
+            .world_mut()
+            .spawn((transform, GlobalTransform::default()))
+            .id();
+        let mut cam = TerminalCamera::new();
+        cam.set_cursor_world_pos(Some(Vec2::new(2.5, 3.5)));
+        app.world_mut().spawn(cam);
+        (app, term)
+    }
+
+    fn events(app: &mut App) -> Vec<TerminalMouseEventKind> {
+        app.world_mut()
+            .resource_mut::<Events<TerminalMouseEvent>>()
+            .drain()
+            .map(|e| e.kind)
+            .collect()
+    }
+
+    fn button(app: &mut App, state: ButtonState) {
+        app.world_mut().send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    #[test]
+    fn hover_and_click() {
+        let (mut app, term) = setup();
+        app.update();
+        let tile = IVec2::new(2, 3);
+        assert_eq!(
+            Some((term, tile)),
+            app.world().resource::<TerminalMouse>().hovered()
+        );
+        assert_eq!(vec![TerminalMouseEventKind::Enter], events(&mut app));
+
+        button(&mut app, ButtonState::Pressed);
+        app.update();
+        assert_eq!(
+            vec![TerminalMouseEventKind::Press(MouseButton::Left)],
+            events(&mut app)
+        );
+
+        button(&mut app, ButtonState::Released);
+        app.update();
+        assert_eq!(
+            vec![
+                TerminalMouseEventKind::Release(MouseButton::Left),
+                TerminalMouseEventKind::Click(MouseButton::Left)
+            ],
+            events(&mut app)
+        );
+    }
+
+    #[test]
+    fn wheel_units() {
+        let (mut app, _) = setup();
+        app.update();
+        events(&mut app);
+        for (unit, y) in [(MouseScrollUnit::Line, 1.0), (MouseScrollUnit::Pixel, 40.0)] {
+            app.world_mut().send_event(MouseWheel {
+                unit,
+                x: 0.0,
+                y,
+                window: Entity::PLACEHOLDER,
+            });
+        }
+        app.update();
+        assert_eq!(
+            vec![TerminalMouseEventKind::Wheel(Vec2::new(0.0, 3.0))],
+            events(&mut app)
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! A [bevy::picking] backend for terminals.
+//!
+//! Terminals are hit tested against their mesh bounds, including the border,
+//! using the cached [TerminalTransform] data. The hit tile for each pointer
+//! is stored in the terminal's [TerminalPointerTiles] component.
+
+use std::cmp::Reverse;
+
+use bevy::{
+    app::{Plugin, PreUpdate},
+    ecs::{
+        component::Component, entity::Entity, event::EventWriter, query::With,
+        schedule::IntoSystemConfigs, system::Query,
+    },
+    math::{FloatOrd, IVec2},
+    picking::{
+        backend::{HitData, PointerHits},
+        pointer::{PointerId, PointerLocation},
+        PickSet, PickingBehavior,
+    },
+    render::{
+        camera::{Camera, OrthographicProjection},
+        view::{RenderLayers, ViewVisibility},
+    },
+    transform::components::GlobalTransform,
+    utils::HashMap,
+    window::PrimaryWindow,
+};
+
+use crate::{render::shares_render_layer, transform::TerminalTransform, Terminal};
+
+pub(crate) struct TerminalPickingPlugin;
+
+impl Plugin for TerminalPickingPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.register_required_components::<Terminal, TerminalPointerTiles>()
+            .add_systems(PreUpdate, terminal_picking.in_set(PickSet::Backend));
+    }
+}
+
+/// The terminal tile currently under each pointer that hits the terminal.
+///
+/// This is updated by the picking backend every frame, so it can be read from
+/// picking observers to find the tile that was hit.
+///
+/// Border tiles are included, so a tile may be outside the bounds of the
+/// terminal.
+///
+/// # Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_ascii_terminal::{picking::TerminalPointerTiles, *};
+///
+/// fn setup(mut commands: Commands) {
+///     commands.spawn(Terminal::new([10, 10])).observe(
+///         |trigger: Trigger<Pointer<Click>>, mut q: Query<(&mut Terminal, &TerminalPointerTiles)>| {
+///             let (mut term, tiles) = q.get_mut(trigger.entity()).unwrap();
+///             if let Some(tile) = tiles.get(trigger.pointer_id) {
+///                 term.put_char(tile, 'X');
+///             }
+///         },
+///     );
+/// }
+/// ```
+#[derive(Component, Default, Debug, Clone)]
+pub struct TerminalPointerTiles(HashMap<PointerId, IVec2>);
+
+impl TerminalPointerTiles {
+    /// The tile under the given pointer, if the pointer is over the terminal.
+    pub fn get(&self, pointer: PointerId) -> Option<IVec2> {
+        self.0.get(&pointer).copied()
+    }
+
+    /// Iterate over all pointers currently over the terminal along with the
+    /// tile they are over.
+    pub fn iter(&self) -> impl Iterator<Item = (PointerId, IVec2)> + '_ {
+        self.0.iter().map(|(p, t)| (*p, *t))
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn terminal_picking(
+    pointers: Query<(&PointerId, &PointerLocation)>,
+    cameras: Query<(
+        Entity,
+        &Camera,
+        &GlobalTransform,
+        &OrthographicProjection,
+        Option<&RenderLayers>,
+    )>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    mut q_term: Query<(
+        Entity,
+        &TerminalTransform,
+        &GlobalTransform,
+        &ViewVisibility,
+        &mut TerminalPointerTiles,
+        Option<&PickingBehavior>,
+        Option<&RenderLayers>,
+    )>,
+    mut output: EventWriter<PointerHits>,
+) {
+    for (_, _, _, _, mut tiles, ..) in &mut q_term {
+        if !tiles.0.is_empty() {
+            tiles.0.clear();
+        }
+    }
+
+    let primary_window = primary_window.get_single().ok();
+
+    for (pointer, location) in pointers
+        .iter()
+        .filter_map(|(pointer, location)| location.location().map(|loc| (pointer, loc)))
+    {
+        let Some((cam_entity, camera, cam_transform, proj, cam_layers)) = cameras
+            .iter()
+            .filter(|(_, camera, ..)| camera.is_active)
+            .filter(|(_, camera, ..)| {
+                camera
+                    .target
+                    .normalize(primary_window)
+                    .is_some_and(|target| target == location.target)
+            })
+            .find(|(_, camera, ..)| {
+                camera
+                    .logical_viewport_rect()
+                    .is_none_or(|rect| rect.contains(location.position))
+            })
+        else {
+            continue;
+        };
+
+        let viewport_pos = camera
+            .logical_viewport_rect()
+            .map(|v| v.min)
+            .unwrap_or_default();
+        let Ok(world_pos) =
+            camera.viewport_to_world_2d(cam_transform, location.position - viewport_pos)
+        else {
+            continue;
+        };
+
+        let mut terminals: Vec<_> = q_term
+            .iter_mut()
+            .filter(|(_, transform, _, vis, .., layers)| {
+                vis.get()
+                    && shares_render_layer(cam_layers, *layers)
+                    && transform
+                        .cached_data
+                        .as_ref()
+                        .is_some_and(|d| d.world_mesh_bounds.contains(world_pos))
+            })
+            .collect();
+        terminals.sort_by_key(|(_, _, gt, ..)| Reverse(FloatOrd(gt.translation().z)));
+
+        let cam_inverse = cam_transform.affine().inverse();
+        let mut picks = Vec::new();
+        for (entity, transform, gt, _, mut tiles, behavior, _) in terminals {
+            let Some(tile) = transform.world_to_tile_unbounded(world_pos) else {
+                continue;
+            };
+            tiles.0.insert(*pointer, tile);
+
+            let hit_pos = world_pos.extend(gt.translation().z);
+            // Depth is measured from the camera's near plane
+            let depth = -proj.near - cam_inverse.transform_point3(hit_pos).z;
+            picks.push((
+                entity,
+                HitData::new(cam_entity, depth, Some(hit_pos), Some(*gt.back())),
+            ));
+
+            if behavior.is_none_or(|b| b.should_block_lower) {
+                break;
+            }
+        }
+        output.send(PointerHits::new(*pointer, picks, camera.order as f32));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        app::App,
+        asset::{AssetEvent, Assets},
+        ecs::event::Events,
+        image::Image,
+        math::{Rect, UVec2, Vec2, Vec3},
+        picking::pointer::Location,
+        render::camera::{camera_system, ManualTextureViews, RenderTarget},
+        window::{Window, WindowCreated, WindowRef, WindowResized, WindowScaleFactorChanged},
+        MinimalPlugins,
+    };
+
+    use super::*;
+    use crate::transform::CachedTransformData;
+
+    #[test]
+    fn pointer_hits_terminal() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<PointerHits>()
+            .add_event::<WindowCreated>()
+            .add_event::<WindowResized>()
+            .add_event::<WindowScaleFactorChanged>()
+            .add_event::<AssetEvent<Image>>()
+            .init_resource::<Assets<Image>>()
+            .init_resource::<ManualTextureViews>()
+            .add_systems(
+                PreUpdate,
+                (camera_system::<OrthographicProjection>, terminal_picking).chain(),
+            );
+
+        let window = app
+            .world_mut()
+            .spawn((Window::default(), PrimaryWindow))
+            .id();
+        let size = app.world().get::<Window>(window).unwrap().resolution.size();
+        app.world_mut().spawn((
+            Camera::default(),
+            OrthographicProjection::default_2d(),
+            GlobalTransform::default(),
+        ));
+        let mut visibility = ViewVisibility::default();
+        visibility.set();
+        let transform = TerminalTransform {
+            cached_data: Some(CachedTransformData {
+                world_tile_size: Vec2::ONE,
+                terminal_size: UVec2::new(10, 5),
+                local_inner_mesh_bounds: Rect::new(0.0, 0.0, 10.0, 5.0),
+                world_mesh_bounds: Rect::new(0.0, 0.0, 10.0, 5.0),
+                world_pos: Vec3::ZERO,
+                pixels_per_tile: UVec2::splat(8),
+            }),
+        };
+        let term = app
+            .world_mut()
+            .spawn((
+                transform,
+                GlobalTransform::default(),
+                visibility,
+                TerminalPointerTiles::default(),
+            ))
+            .id();
+        // The camera is centered on the window and ui positions point down
+        let target = RenderTarget::Window(WindowRef::Primary)
+            .normalize(Some(window))
+            .unwrap();
+        let position = size / 2.0 + Vec2::new(2.5, -3.5);
+        app.world_mut().spawn((
+            PointerId::Mouse,
+            PointerLocation::new(Location { target, position }),
+        ));
+        app.update();
+
+        let hits: Vec<_> = app
+            .world_mut()
+            .resource_mut::<Events<PointerHits>>()
+            .drain()
+            .collect();
+        assert_eq!(1, hits.len());
+        assert_eq!(PointerId::Mouse, hits[0].pointer);
+        assert_eq!(
+            vec![term],
+            hits[0].picks.iter().map(|(e, _)| *e).collect::<Vec<_>>()
+        );
+        let tiles = app.world().get::<TerminalPointerTiles>(term).unwrap();
+        assert_eq!(Some(IVec2::new(2, 3)), tiles.get(PointerId::Mouse));
+    }
+}
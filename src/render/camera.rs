@@ -1,26 +1,42 @@
 use bevy::{
-    app::{First, Plugin},
+    app::{First, Plugin, PostUpdate},
     asset::{AssetEvent, Assets},
     ecs::{
+        change_detection::DetectChangesMut,
         component::Component,
         entity::Entity,
         event::{Event, EventReader, EventWriter},
-        query::{Changed, Or, With},
+        query::{Changed, Or, With, Without},
         schedule::{IntoSystemConfigs, SystemSet},
         system::{Query, Res},
     },
     image::Image,
     math::{Mat4, UVec2, Vec2},
     prelude::Camera2d,
-    render::camera::{Camera, OrthographicProjection, ScalingMode, Viewport},
+    render::{
+        camera::{
+            Camera, CameraUpdateSystem, NormalizedRenderTarget, OrthographicProjection,
+            ScalingMode, Viewport,
+        },
+        view::RenderLayers,
+    },
     sprite::MeshMaterial2d,
-    transform::components::{GlobalTransform, Transform},
+    transform::{
+        components::{GlobalTransform, Transform},
+        TransformSystem,
+    },
     window::{PrimaryWindow, Window, WindowResized},
 };
 
-use crate::{transform::TerminalTransform, Terminal};
+use crate::{
+    transform::{TerminalSystemsUpdateTransform, TerminalTransform},
+    Terminal,
+};
 
-use super::{TerminalMaterial, TerminalMeshWorldScaling};
+use super::{
+    camera_follow::{update_follow_camera, TerminalCameraFollow},
+    TerminalMaterial, TerminalMeshWorldScaling,
+};
 
 pub struct TerminalCameraPlugin;
 
@@ -28,7 +44,8 @@ pub struct TerminalCameraPlugin;
 // #[derive(Debug, Default, Clone, Eq, PartialEq, Hash, SystemSet)]
 // pub struct TerminalSystemsCacheCameraData;
 
-/// [TerminalCamera] systems for updating the camera viewport.
+/// [TerminalCamera] systems for updating the camera viewport. Runs in [First],
+/// and in [PostUpdate] for [TerminalCameraFollow] cameras.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Hash, SystemSet)]
 pub struct TerminalSystemsUpdateCamera;
 
@@ -48,11 +65,20 @@ impl Plugin for TerminalCameraPlugin {
                     cache_cursor_data,
                     cache_camera_data,
                     on_window_resized,
+                    on_camera_changed,
                     on_font_changed,
                     update_viewport,
                 )
                     .chain()
                     .in_set(TerminalSystemsUpdateCamera),
+            )
+            .add_systems(
+                PostUpdate,
+                update_follow_camera
+                    .in_set(TerminalSystemsUpdateCamera)
+                    .after(TerminalSystemsUpdateTransform)
+                    .before(TransformSystem::TransformPropagate)
+                    .before(CameraUpdateSystem),
             );
     }
 }
@@ -62,10 +88,45 @@ pub struct UpdateTerminalViewportEvent;
 
 /// A camera component to assist in rendering terminals and translating
 /// cursor coordinates to and from terminal grid coordinates.
+///
+/// Multiple terminal cameras can exist at once, each rendering to it's own
+/// window or render target. A camera will only frame the terminals that share
+/// at least one of it's [RenderLayers]. Entities with no [RenderLayers]
+/// component are on layer 0.
+///
+/// # Example
+///
+/// ```no_run
+/// use bevy::{prelude::*, render::{camera::RenderTarget, view::RenderLayers}, window::WindowRef};
+/// use bevy_ascii_terminal::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // Rendered to the primary window on the default render layer
+///     commands.spawn(Terminal::new([40, 20]));
+///     commands.spawn(TerminalCamera::new());
+///
+///     // Rendered to a second window on render layer 1
+///     let window = commands.spawn(Window::default()).id();
+///     commands.spawn((Terminal::new([20, 10]), RenderLayers::layer(1)));
+///     commands.spawn((
+///         TerminalCamera::new(),
+///         Camera {
+///             target: RenderTarget::Window(WindowRef::Entity(window)),
+///             ..default()
+///         },
+///         RenderLayers::layer(1),
+///     ));
+/// }
+/// ```
 #[derive(Component)]
 #[require(Camera2d, Transform(cam_transform))]
 pub struct TerminalCamera {
     pub track_cursor: bool,
+    /// How terminals are scaled to fit the camera's render target.
+    pub scaling: TerminalCameraScaling,
+    /// A specific terminal entity to frame. If [None] the camera will frame
+    /// all terminals on it's render layers.
+    pub frame_target: Option<Entity>,
     cam_data: Option<CachedCameraData>,
     cursor_data: Option<CachedCursorData>,
 }
@@ -78,19 +139,61 @@ impl Default for TerminalCamera {
     fn default() -> Self {
         Self {
             track_cursor: true,
+            scaling: Default::default(),
+            frame_target: None,
             cam_data: Default::default(),
             cursor_data: Default::default(),
         }
     }
 }
 
+/// Determines how a [TerminalCamera] scales the terminals it's framing to fit
+/// it's render target.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum TerminalCameraScaling {
+    /// Scale by the largest integer zoom that fits the render target,
+    /// letterboxing any remaining space. This keeps the terminals pixel
+    /// perfect.
+    #[default]
+    Integer,
+    /// Scale by the largest fractional zoom that fits the render target,
+    /// letterboxing any remaining space.
+    Fit,
+    /// Scale by the smallest fractional zoom that covers the entire render
+    /// target, cropping the edges of the terminals.
+    Fill,
+    /// Scale by the largest integer zoom that fits the render target, and
+    /// expand the viewport to cover the entire render target. Any extra space
+    /// shows the world around the terminals rather than being letterboxed.
+    ///
+    /// This can be combined with [crate::TerminalAutoResize] to grow a terminal
+    /// to fill the window.
+    Expand,
+}
+
 impl TerminalCamera {
     pub fn new() -> Self {
         Self::default()
     }
 
-    /// Returns the world position of the main window cursor using the last
-    /// cached camera data.
+    /// Set how the terminals are scaled to fit the camera's render target.
+    pub fn with_scaling(mut self, scaling: TerminalCameraScaling) -> Self {
+        self.scaling = scaling;
+        self
+    }
+
+    /// Frame a specific terminal entity rather than all terminals.
+    pub fn with_frame_target(mut self, terminal: Entity) -> Self {
+        self.frame_target = Some(terminal);
+        self
+    }
+
+    /// Returns the world position of the cursor using the last cached camera
+    /// data.
+    ///
+    /// The cursor is read from the camera's target window, and will be [None]
+    /// if the camera doesn't target a window or the cursor is outside the
+    /// camera's viewport.
     ///
     /// Will return [None] if the camera data has not been initialized.
     ///
@@ -100,8 +203,8 @@ impl TerminalCamera {
         self.cursor_data.as_ref().map(|v| v.world_pos)
     }
 
-    /// The viewport position of the main window cursor as of the last camera
-    /// update.
+    /// The viewport position of the cursor in the camera's target window as of
+    /// the last camera update.
     ///
     /// Will return [None] if the camera data has not been initialized.
     ///
@@ -111,6 +214,28 @@ impl TerminalCamera {
         self.cursor_data.as_ref().map(|v| v.viewport_pos)
     }
 
+    /// Set the cached cursor position directly, for tests without a window.
+    #[cfg(test)]
+    pub(crate) fn set_cursor_world_pos(&mut self, world_pos: Option<Vec2>) {
+        self.cursor_data = world_pos.map(|world_pos| CachedCursorData {
+            viewport_pos: Vec2::ZERO,
+            world_pos,
+        });
+    }
+
+    /// True if the given window position is inside the camera's viewport, as
+    /// of the last camera update.
+    fn viewport_contains(&self, viewport_position: Vec2) -> bool {
+        let Some(data) = self.cam_data.as_ref() else {
+            return false;
+        };
+        let (Some(size), min) = (data.target_size, data.vp_offset.unwrap_or_default()) else {
+            return true;
+        };
+        let pos = viewport_position - min;
+        pos.cmpge(Vec2::ZERO).all() && pos.cmplt(size).all()
+    }
+
     /// Transform a viewport position to it's corresponding world position using
     /// the last cached camera data.
     ///
@@ -141,6 +266,45 @@ struct CachedCameraData {
     proj_matrix: Mat4,
     target_size: Option<Vec2>,
     vp_offset: Option<Vec2>,
+    window: Option<Entity>,
+}
+
+/// Returns true if two entities share a render layer, treating a missing
+/// [RenderLayers] component as the default layer.
+pub(crate) fn shares_render_layer(a: Option<&RenderLayers>, b: Option<&RenderLayers>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.intersects(b),
+        (Some(layers), None) | (None, Some(layers)) => layers.intersects(&RenderLayers::default()),
+        (None, None) => true,
+    }
+}
+
+/// The window entity a camera renders to, if any.
+fn target_window(cam: &Camera, primary_window: Option<Entity>) -> Option<Entity> {
+    match cam.target.normalize(primary_window)? {
+        NormalizedRenderTarget::Window(window) => Some(window.entity()),
+        _ => None,
+    }
+}
+
+/// The current physical size of a camera's render target.
+///
+/// Window and image sizes are read directly since the camera's own cached
+/// target size isn't updated until later in the frame.
+pub(super) fn target_physical_size(
+    cam: &Camera,
+    primary_window: Option<Entity>,
+    q_window: &Query<&Window>,
+    images: &Assets<Image>,
+) -> Option<UVec2> {
+    match cam.target.normalize(primary_window)? {
+        NormalizedRenderTarget::Window(window) => q_window
+            .get(window.entity())
+            .ok()
+            .map(|w| UVec2::new(w.physical_width(), w.physical_height())),
+        NormalizedRenderTarget::Image(handle) => images.get(&handle).map(|i| i.size()),
+        _ => cam.physical_target_size(),
+    }
 }
 
 #[derive(Default, Debug, Clone)]
@@ -155,8 +319,12 @@ fn cache_camera_data(
         (&mut TerminalCamera, &GlobalTransform, &Camera),
         Or<(Changed<Camera>, Changed<GlobalTransform>)>,
     >,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
 ) {
+    let primary_window = primary_window.get_single().ok();
     for (mut terminal_cam, transform, cam) in &mut q_cam {
+        // Cached data is internal, settings changes are detected separately
+        let terminal_cam = terminal_cam.bypass_change_detection();
         if !terminal_cam.track_cursor {
             if terminal_cam.cam_data.is_some() {
                 terminal_cam.cam_data = None;
@@ -168,16 +336,14 @@ fn cache_camera_data(
             proj_matrix: cam.clip_from_view(),
             target_size: cam.logical_viewport_size(),
             vp_offset: cam.logical_viewport_rect().map(|vp| vp.min),
+            window: target_window(cam, primary_window),
         });
     }
 }
 
-fn cache_cursor_data(
-    mut q_cam: Query<&mut TerminalCamera>,
-    window: Query<&Window, With<PrimaryWindow>>,
-) {
-    let cursor_viewport_pos = window.get_single().ok().and_then(|w| w.cursor_position());
+fn cache_cursor_data(mut q_cam: Query<&mut TerminalCamera>, q_window: Query<&Window>) {
     for mut terminal_cam in &mut q_cam {
+        let terminal_cam = terminal_cam.bypass_change_detection();
         if !terminal_cam.track_cursor {
             if terminal_cam.cursor_data.is_some() {
                 terminal_cam.cursor_data = None;
@@ -185,7 +351,15 @@ fn cache_cursor_data(
             continue;
         };
 
+        let cursor_viewport_pos = terminal_cam
+            .cam_data
+            .as_ref()
+            .and_then(|data| data.window)
+            .and_then(|window| q_window.get(window).ok())
+            .and_then(|window| window.cursor_position());
+
         let Some((viewport_pos, world_pos)) = cursor_viewport_pos
+            .filter(|vp| terminal_cam.viewport_contains(*vp))
             .and_then(|vp| terminal_cam.viewport_to_world(vp).map(|wp| (vp, wp)))
         else {
             terminal_cam.cursor_data = None;
@@ -199,20 +373,39 @@ fn cache_cursor_data(
 }
 
 fn on_window_resized(
-    q_win: Query<Entity, With<PrimaryWindow>>,
+    q_cam: Query<&TerminalCamera>,
     mut resize_events: EventReader<WindowResized>,
     mut vp_evt: EventWriter<UpdateTerminalViewportEvent>,
 ) {
-    if q_win.is_empty() || resize_events.is_empty() {
+    if q_cam.is_empty() || resize_events.is_empty() {
         return;
     }
-    let primary_window = q_win.single();
     for resize_event in resize_events.read() {
-        if resize_event.window != primary_window {
-            continue;
+        let is_target = q_cam.iter().any(|cam| {
+            cam.cam_data
+                .as_ref()
+                .is_none_or(|data| data.window == Some(resize_event.window))
+        });
+        if is_target {
+            vp_evt.send(UpdateTerminalViewportEvent);
+            return;
         }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn on_camera_changed(
+    q_cam: Query<
+        (),
+        (
+            With<TerminalCamera>,
+            Or<(Changed<TerminalCamera>, Changed<RenderLayers>)>,
+        ),
+    >,
+    mut vp_evt: EventWriter<UpdateTerminalViewportEvent>,
+) {
+    if !q_cam.is_empty() {
         vp_evt.send(UpdateTerminalViewportEvent);
-        return;
     }
 }
 
@@ -253,13 +446,22 @@ fn on_font_changed(
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn update_viewport(
-    q_term: Query<&TerminalTransform>,
+    q_term: Query<(Entity, &TerminalTransform, Option<&RenderLayers>)>,
     mut q_cam: Query<
-        (&mut Camera, &mut Transform, &mut OrthographicProjection),
-        With<TerminalCamera>,
+        (
+            &TerminalCamera,
+            &mut Camera,
+            &mut Transform,
+            &mut OrthographicProjection,
+            Option<&RenderLayers>,
+        ),
+        (With<TerminalCamera>, Without<TerminalCameraFollow>),
     >,
-    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_window: Query<&Window>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    images: Res<Assets<Image>>,
     scaling: Res<TerminalMeshWorldScaling>,
     mut update_evt: EventReader<UpdateTerminalViewportEvent>,
 ) {
@@ -267,39 +469,81 @@ fn update_viewport(
         return;
     }
 
-    let Ok((mut cam, mut cam_transform, mut proj)) = q_cam.get_single_mut() else {
-        return;
-    };
-    let Ok(window) = q_window.get_single() else {
-        return;
-    };
+    let primary_window = primary_window.get_single().ok();
+    let mut pending = false;
+    for (terminal_cam, mut cam, mut cam_transform, mut proj, cam_layers) in &mut q_cam {
+        let Some(target_res) = target_physical_size(&cam, primary_window, &q_window, &images)
+        else {
+            // Render target may not exist yet
+            pending = true;
+            continue;
+        };
+        let terminals: Vec<_> = q_term
+            .iter()
+            .filter(|(e, _, layers)| match terminal_cam.frame_target {
+                Some(target) => *e == target,
+                None => shares_render_layer(cam_layers, *layers),
+            })
+            .map(|(_, t, _)| t)
+            .collect();
+        if terminals.is_empty() {
+            continue;
+        }
+        if !frame_terminals(
+            &terminals,
+            target_res.as_vec2(),
+            &scaling,
+            terminal_cam.scaling,
+            &mut cam,
+            &mut cam_transform,
+            &mut proj,
+        ) {
+            pending = true;
+        }
+    }
+
+    if !pending {
+        update_evt.clear();
+    }
+}
 
+/// Frame the given terminals with a camera. Returns false if the terminal
+/// data isn't ready yet.
+fn frame_terminals(
+    terminals: &[&TerminalTransform],
+    window_res: Vec2,
+    scaling: &TerminalMeshWorldScaling,
+    cam_scaling: TerminalCameraScaling,
+    cam: &mut Camera,
+    cam_transform: &mut Transform,
+    proj: &mut OrthographicProjection,
+) -> bool {
     // Determine our canonical 'pixels per unit' from the terminal
     // with the largest font.
-    let Some(ppu) = q_term
+    let Some(ppu) = terminals
         .iter()
         .filter_map(|t| t.cached_data.as_ref().map(|d| d.pixels_per_tile))
         .reduce(UVec2::max)
     else {
         // Terminal font images may still be loading
-        return;
+        return false;
     };
     // Determine our canonical tile size from the largest of all terminals.
-    let Some(tile_size) = q_term
+    let Some(tile_size) = terminals
         .iter()
         .filter_map(|t| t.cached_data.as_ref().map(|d| d.world_tile_size))
         .reduce(Vec2::max)
     else {
-        return;
+        return false;
     };
 
     // Invalid terminal image size, images could still be loading.
     if ppu.cmpeq(UVec2::ZERO).any() {
-        return;
+        return false;
     }
 
     // The total bounds of all terminal meshes in world space
-    let mesh_bounds = q_term
+    let mesh_bounds = terminals
         .iter()
         .map(|t| {
             t.cached_data
@@ -322,30 +566,44 @@ fn update_viewport(
 
     let target_res = tile_count.as_vec2() * ppu.as_vec2();
 
-    let window_res = UVec2::new(window.physical_width(), window.physical_height()).as_vec2();
-    let zoom = (window_res / target_res).floor().min_element().max(1.0);
-
-    let vp_size = (target_res * zoom).max(Vec2::ONE);
-    let vp_pos = if window_res.cmple(target_res).any() {
-        Vec2::ZERO
-    } else {
-        (window_res / 2.0) - (vp_size / 2.0)
-    }
-    .floor();
-
-    if vp_size.cmpgt(window_res).any() {
-        cam.viewport = None;
-    } else {
-        cam.viewport = Some(Viewport {
-            physical_position: vp_pos.as_uvec2(),
-            physical_size: vp_size.as_uvec2(),
-            ..Default::default()
-        });
-    }
+    let fit = window_res / target_res;
+    let zoom = match cam_scaling {
+        TerminalCameraScaling::Integer | TerminalCameraScaling::Expand => {
+            fit.floor().min_element().max(1.0)
+        }
+        TerminalCameraScaling::Fit => fit.min_element(),
+        TerminalCameraScaling::Fill => fit.max_element(),
+    };
 
-    proj.scaling_mode = ScalingMode::FixedVertical {
-        viewport_height: ortho_size,
+    let viewport_height = match cam_scaling {
+        TerminalCameraScaling::Integer | TerminalCameraScaling::Fit => {
+            let vp_size = (target_res * zoom).max(Vec2::ONE);
+            let vp_pos = if window_res.cmple(target_res).any() {
+                Vec2::ZERO
+            } else {
+                (window_res / 2.0) - (vp_size / 2.0)
+            }
+            .floor();
+
+            if vp_size.cmpgt(window_res).any() {
+                cam.viewport = None;
+            } else {
+                cam.viewport = Some(Viewport {
+                    physical_position: vp_pos.as_uvec2(),
+                    physical_size: vp_size.as_uvec2(),
+                    ..Default::default()
+                });
+            }
+            ortho_size
+        }
+        // The viewport covers the whole target, so the visible world area
+        // depends on the target size rather than the terminals
+        TerminalCameraScaling::Fill | TerminalCameraScaling::Expand => {
+            cam.viewport = None;
+            window_res.y / zoom * (ortho_size / target_res.y)
+        }
     };
 
-    update_evt.clear();
+    proj.scaling_mode = ScalingMode::FixedVertical { viewport_height };
+    true
 }
@@ -0,0 +1,250 @@
+use bevy::{
+    asset::Assets,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::With,
+        system::{Query, Res},
+    },
+    image::Image,
+    math::{IVec2, UVec2, Vec2},
+    render::camera::{Camera, OrthographicProjection, ScalingMode},
+    time::Time,
+    transform::components::{GlobalTransform, Transform},
+    window::{PrimaryWindow, Window},
+};
+
+use crate::{transform::TerminalTransform, GridPoint};
+
+use super::{camera::target_physical_size, TerminalCamera};
+
+/// A component which makes a [TerminalCamera] follow a target over a terminal,
+/// rather than framing the entire terminal.
+///
+/// The camera renders the terminal at a fixed pixel perfect zoom, smoothly
+/// moves towards it's target and is clamped so it never shows anything outside
+/// the terminal's mesh bounds. This is intended for large map terminals which
+/// don't fit on the screen.
+///
+/// # Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_ascii_terminal::*;
+///
+/// fn setup(mut commands: Commands) {
+///     let map = commands.spawn(Terminal::new([200, 200])).id();
+///     commands.spawn((
+///         TerminalCamera::new(),
+///         TerminalCameraFollow::new(map, [100, 100]).with_zoom(2),
+///     ));
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+#[require(TerminalCamera)]
+pub struct TerminalCameraFollow {
+    /// The terminal the camera is following over. The camera will be clamped
+    /// to the terminal's mesh bounds and [FollowTarget::Tile] targets are
+    /// relative to this terminal.
+    pub terminal: Entity,
+    pub target: FollowTarget,
+    /// How quickly the camera moves towards it's target. Higher values are
+    /// faster, a value of 0 will snap the camera directly to the target.
+    pub smoothing: f32,
+    /// The number of window pixels per font pixel. Values less than 1 are
+    /// treated as 1.
+    pub zoom: u32,
+    /// The maximum zoom level for [TerminalCameraFollow::zoom_in].
+    pub max_zoom: u32,
+    /// The unsnapped position of the camera, to prevent smoothing from
+    /// stalling when the camera is within a pixel of it's target.
+    position: Option<Vec2>,
+}
+
+/// The target for a [TerminalCameraFollow] camera.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowTarget {
+    /// A tile position in the followed terminal.
+    Tile(IVec2),
+    /// The position of an entity.
+    Entity(Entity),
+}
+
+impl TerminalCameraFollow {
+    /// Follow a tile position in the given terminal.
+    pub fn new(terminal: Entity, tile: impl GridPoint) -> Self {
+        Self {
+            terminal,
+            target: FollowTarget::Tile(tile.to_ivec2()),
+            smoothing: 8.0,
+            zoom: 1,
+            max_zoom: 8,
+            position: None,
+        }
+    }
+
+    /// Follow an entity over the given terminal.
+    pub fn new_entity(terminal: Entity, target: Entity) -> Self {
+        Self {
+            target: FollowTarget::Entity(target),
+            ..Self::new(terminal, [0, 0])
+        }
+    }
+
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    pub fn with_zoom(mut self, zoom: u32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    /// Set the target to a tile position in the followed terminal.
+    pub fn set_tile(&mut self, tile: impl GridPoint) {
+        self.target = FollowTarget::Tile(tile.to_ivec2());
+    }
+
+    /// Increase the zoom by one step, up to [TerminalCameraFollow::max_zoom].
+    pub fn zoom_in(&mut self) {
+        self.zoom = (self.zoom + 1).min(self.max_zoom.max(1));
+    }
+
+    /// Decrease the zoom by one step, down to a minimum of 1.
+    pub fn zoom_out(&mut self) {
+        self.zoom = self.zoom.saturating_sub(1).max(1);
+    }
+
+    /// Snap the camera directly to it's target on the next update, ignoring
+    /// smoothing.
+    pub fn snap(&mut self) {
+        self.position = None;
+    }
+}
+
+/// Clamp a camera position so the visible area stays within the given bounds,
+/// centering on any axis where the visible area is larger than the bounds.
+fn clamp_to_bounds(pos: Vec2, visible: Vec2, bounds: bevy::math::Rect) -> Vec2 {
+    let half = visible / 2.0;
+    let min = bounds.min + half;
+    let max = bounds.max - half;
+    let center = bounds.center();
+    Vec2::new(
+        if min.x > max.x {
+            center.x
+        } else {
+            pos.x.clamp(min.x, max.x)
+        },
+        if min.y > max.y {
+            center.y
+        } else {
+            pos.y.clamp(min.y, max.y)
+        },
+    )
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_follow_camera(
+    mut q_cam: Query<(
+        &mut TerminalCameraFollow,
+        &mut Camera,
+        &mut Transform,
+        &mut OrthographicProjection,
+    )>,
+    q_term: Query<&TerminalTransform>,
+    q_target: Query<&GlobalTransform>,
+    q_window: Query<&Window>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    images: Res<Assets<Image>>,
+    time: Res<Time>,
+) {
+    let primary_window = primary_window.get_single().ok();
+    for (mut follow, mut cam, mut transform, mut proj) in &mut q_cam {
+        let Some(data) = q_term
+            .get(follow.terminal)
+            .ok()
+            .and_then(|t| t.cached_data.as_ref().map(|d| (t, d)))
+        else {
+            continue;
+        };
+        let (term_transform, data) = data;
+        // Font images may still be loading
+        if data.pixels_per_tile.cmpeq(UVec2::ZERO).any() {
+            continue;
+        }
+        let Some(target_res) = target_physical_size(&cam, primary_window, &q_window, &images)
+            .filter(|r| r.cmpgt(UVec2::ZERO).all())
+        else {
+            continue;
+        };
+        let target = match follow.target {
+            FollowTarget::Tile(tile) => term_transform.tile_to_world(tile),
+            FollowTarget::Entity(e) => q_target.get(e).ok().map(|t| t.translation().truncate()),
+        };
+        let Some(target) = target else {
+            continue;
+        };
+
+        // World units per window pixel at the current zoom
+        let zoom = follow.zoom.max(1) as f32;
+        let units_per_pixel = data.world_tile_size / data.pixels_per_tile.as_vec2() / zoom;
+        let visible = target_res.as_vec2() * units_per_pixel;
+
+        let target = clamp_to_bounds(target, visible, data.world_mesh_bounds);
+        let current = follow.position.unwrap_or(target);
+        let t = if follow.smoothing > 0.0 {
+            1.0 - (-follow.smoothing * time.delta_secs()).exp()
+        } else {
+            1.0
+        };
+        let pos = current.lerp(target, t);
+        if follow.position != Some(pos) {
+            follow.position = Some(pos);
+        }
+
+        // Snap the rendered position to the pixel grid
+        let snapped = (pos / units_per_pixel).round() * units_per_pixel;
+        if transform.translation.truncate() != snapped {
+            let z = transform.translation.z;
+            transform.translation = snapped.extend(z);
+        }
+        if cam.viewport.is_some() {
+            cam.viewport = None;
+        }
+        let height = match proj.scaling_mode {
+            ScalingMode::FixedVertical { viewport_height } => Some(viewport_height),
+            _ => None,
+        };
+        if height != Some(visible.y) {
+            proj.scaling_mode = ScalingMode::FixedVertical {
+                viewport_height: visible.y,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Rect;
+
+    use super::*;
+
+    #[test]
+    fn clamp() {
+        let bounds = Rect::new(0.0, 0.0, 100.0, 50.0);
+        let visible = Vec2::new(20.0, 80.0);
+        assert_eq!(
+            Vec2::new(10.0, 25.0),
+            clamp_to_bounds(Vec2::new(-5.0, 0.0), visible, bounds)
+        );
+        assert_eq!(
+            Vec2::new(90.0, 25.0),
+            clamp_to_bounds(Vec2::new(200.0, 40.0), visible, bounds)
+        );
+        assert_eq!(
+            Vec2::new(50.0, 25.0),
+            clamp_to_bounds(Vec2::new(50.0, 0.0), visible, bounds)
+        );
+    }
+}
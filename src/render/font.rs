@@ -54,7 +54,7 @@ pub enum TerminalFont {
     JtCurses12x12,
     SazaroteCurses12x12,
     Custom(String),
-    CustomImage(Handle<Image>)
+    CustomImage(Handle<Image>),
 }
 
 macro_rules! font_bytes {
@@ -1,17 +1,19 @@
 mod camera;
+mod camera_follow;
 mod font;
 mod material;
 mod mesh;
 mod uv_mapping;
 
 use bevy::prelude::Resource;
-pub use camera::TerminalCamera;
+pub use camera::{TerminalCamera, TerminalCameraScaling};
+pub use camera_follow::{FollowTarget, TerminalCameraFollow};
 pub use font::TerminalFont;
 pub use material::TerminalMaterial;
 pub use mesh::{RebuildMeshVerts, TerminalMeshPivot, TerminalMeshTileScaling};
 pub use uv_mapping::{UvMapping, UvMappingHandle};
 
-pub(crate) use camera::TerminalCameraPlugin;
+pub(crate) use camera::{shares_render_layer, TerminalCameraPlugin};
 pub(crate) use font::TerminalFontPlugin;
 pub(crate) use material::TerminalMaterialPlugin;
 pub(crate) use mesh::TerminalMeshPlugin;
@@ -0,0 +1,130 @@
+//! Support for loading REXPaint .xp files through bevy's asset server.
+
+use bevy::{
+    app::{Plugin, PostUpdate},
+    asset::{io::Reader, Asset, AssetApp, AssetEvent, AssetLoader, Assets, Handle, LoadContext},
+    ecs::{
+        component::Component,
+        event::EventReader,
+        query::Changed,
+        schedule::IntoSystemConfigs,
+        system::{Query, Res},
+    },
+    log::error,
+    prelude::{Deref, DerefMut},
+    reflect::{Reflect, TypePath},
+};
+
+use crate::{error::TerminalError, transform::TerminalSystemsUpdateTransform, Terminal};
+
+use super::reader::XpFile;
+
+pub(crate) struct TerminalXpAssetPlugin;
+
+impl Plugin for TerminalXpAssetPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_asset::<XpAsset>()
+            .register_asset_loader(XpAssetLoader)
+            .add_systems(
+                PostUpdate,
+                (on_handle_changed, on_asset_event)
+                    .chain()
+                    .before(TerminalSystemsUpdateTransform),
+            );
+    }
+}
+
+/// A REXPaint image loaded through the asset server.
+#[derive(Asset, TypePath, Debug, Clone, Deref, DerefMut)]
+pub struct XpAsset(pub XpFile);
+
+/// An asset loader for REXPaint .xp files.
+#[derive(Default)]
+pub struct XpAssetLoader;
+
+impl AssetLoader for XpAssetLoader {
+    type Asset = XpAsset;
+    type Settings = ();
+    type Error = TerminalError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let xp = XpFile::read(&mut bytes.as_slice())?;
+        Ok(XpAsset(xp))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["xp"]
+    }
+}
+
+/// A component which keeps a terminal in sync with a loaded REXPaint asset.
+///
+/// The terminal will be rebuilt from the asset once it finishes loading and
+/// again any time the asset is modified, for example when hot reloading is
+/// enabled.
+///
+/// Note this will overwrite any existing terminal contents, including the
+/// terminal size.
+///
+/// # Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_ascii_terminal::{rexpaint::XpAssetHandle, *};
+///
+/// fn setup(mut commands: Commands, server: Res<AssetServer>) {
+///     commands.spawn((
+///         Terminal::new([1, 1]),
+///         XpAssetHandle(server.load("hello_rexpaint.xp")),
+///     ));
+/// }
+/// ```
+#[derive(Default, Component, Clone, Debug, Deref, DerefMut, Reflect, PartialEq, Eq)]
+pub struct XpAssetHandle(pub Handle<XpAsset>);
+
+fn apply_xp(term: &mut Terminal, xp: &XpFile) {
+    match Terminal::from_rexpaint(xp) {
+        Ok(new) => *term = new,
+        Err(e) => error!("Error building terminal from REXPaint asset: {e}"),
+    }
+}
+
+fn on_handle_changed(
+    mut q_term: Query<(&mut Terminal, &XpAssetHandle), Changed<XpAssetHandle>>,
+    assets: Res<Assets<XpAsset>>,
+) {
+    for (mut term, handle) in &mut q_term {
+        // If the asset isn't loaded yet it will be applied in `on_asset_event`.
+        if let Some(xp) = assets.get(&handle.0) {
+            apply_xp(&mut term, xp);
+        }
+    }
+}
+
+fn on_asset_event(
+    mut q_term: Query<(&mut Terminal, &XpAssetHandle)>,
+    mut evt: EventReader<AssetEvent<XpAsset>>,
+    assets: Res<Assets<XpAsset>>,
+) {
+    for evt in evt.read() {
+        let id = match evt {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => id,
+            _ => continue,
+        };
+        let Some(xp) = assets.get(*id) else {
+            continue;
+        };
+        for (mut term, handle) in &mut q_term {
+            if handle.id() == *id {
+                apply_xp(&mut term, xp);
+            }
+        }
+    }
+}
@@ -1 +1,8 @@
+//! Loading of REXPaint .xp files, either directly from disk or through bevy's
+//! asset server.
+mod asset;
 pub mod reader;
+
+pub use asset::{XpAsset, XpAssetHandle, XpAssetLoader};
+
+pub(crate) use asset::TerminalXpAssetPlugin;
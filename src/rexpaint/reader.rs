@@ -12,14 +12,24 @@
 #![warn(missing_docs)]
 
 // NOTE: Modified to remove unused write capabilities, flip y coordinates and
-// swap empty tiles from pink background to black background.
+// swap empty tiles from pink background to black background. Invalid headers
+// are reported as `TerminalError`s.
 
 use std::io;
 use std::io::prelude::*;
 
+use bevy::math::UVec2;
 use byteorder::{LittleEndian, ReadBytesExt};
 use flate2::read::GzDecoder;
 
+use crate::TerminalError;
+
+/// The maximum number of layers in a REXPaint image.
+pub const MAX_LAYERS: u32 = 9;
+/// The maximum width or height of a layer. Sizes come from the file header so
+/// they're checked before anything is allocated.
+pub const MAX_LAYER_SIZE: u32 = 4096;
+
 /// Structure representing the components of one color
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct XpColor {
@@ -106,17 +116,31 @@ pub struct XpFile {
 
 impl XpFile {
     /// Read a xp image from a stream
-    pub fn read<R: Read>(f: &mut R) -> io::Result<XpFile> {
+    ///
+    /// Returns [TerminalError::InvalidFormat] if the file has too many layers
+    /// and [TerminalError::InvalidDimensions] if a layer is too large.
+    pub fn read<R: Read>(f: &mut R) -> Result<XpFile, TerminalError> {
         let mut rdr = GzDecoder::new(f);
         let version = rdr.read_i32::<LittleEndian>()?;
         let num_layers = rdr.read_u32::<LittleEndian>()?;
+        if num_layers > MAX_LAYERS {
+            return Err(TerminalError::InvalidFormat(format!(
+                "Too many REXPaint layers: {}",
+                num_layers
+            )));
+        }
 
         let mut layers = Vec::<XpLayer>::with_capacity(num_layers as usize);
         for _layer in 0..num_layers {
-            let width = rdr.read_u32::<LittleEndian>()? as usize;
-            let height = rdr.read_u32::<LittleEndian>()? as usize;
+            let width = rdr.read_u32::<LittleEndian>()?;
+            let height = rdr.read_u32::<LittleEndian>()?;
+            let len = (width <= MAX_LAYER_SIZE && height <= MAX_LAYER_SIZE)
+                .then(|| (width as usize).checked_mul(height as usize))
+                .flatten()
+                .ok_or(TerminalError::InvalidDimensions(UVec2::new(width, height)))?;
+            let (width, height) = (width as usize, height as usize);
 
-            let mut cells = Vec::<XpCell>::with_capacity(width * height);
+            let mut cells = Vec::<XpCell>::with_capacity(len);
             for _y in 0..width {
                 // column-major order
                 for _x in 0..height {
@@ -141,3 +165,50 @@ impl XpFile {
         Ok(XpFile { version, layers })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use byteorder::{LittleEndian, WriteBytesExt};
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    fn xp_bytes(header: &[u32]) -> Vec<u8> {
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        for v in header {
+            enc.write_u32::<LittleEndian>(*v).unwrap();
+        }
+        enc.flush().unwrap();
+        enc.finish().unwrap()
+    }
+
+    #[test]
+    fn untrusted_header() {
+        let bytes = xp_bytes(&[1, u32::MAX]);
+        assert!(matches!(
+            XpFile::read(&mut bytes.as_slice()),
+            Err(TerminalError::InvalidFormat(_))
+        ));
+        let bytes = xp_bytes(&[1, 1, u32::MAX, u32::MAX]);
+        assert!(matches!(
+            XpFile::read(&mut bytes.as_slice()),
+            Err(TerminalError::InvalidDimensions(_))
+        ));
+        let bytes = xp_bytes(&[1, 1, MAX_LAYER_SIZE + 1, 1]);
+        assert!(matches!(
+            XpFile::read(&mut bytes.as_slice()),
+            Err(TerminalError::InvalidDimensions(_))
+        ));
+        let bytes = xp_bytes(&[1, 1, 2, 2]);
+        assert!(matches!(
+            XpFile::read(&mut bytes.as_slice()),
+            Err(TerminalError::Io(_))
+        ));
+
+        let bytes = xp_bytes(&[1, 1, 0, 0]);
+        let xp = XpFile::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(1, xp.layers.len());
+    }
+}
@@ -0,0 +1,123 @@
+//! Support for loading terminal snapshots through bevy's asset server.
+
+use bevy::{
+    app::{Plugin, PostUpdate},
+    asset::{io::Reader, AssetApp, AssetEvent, AssetLoader, Assets, Handle, LoadContext},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::EventReader,
+        query::Changed,
+        schedule::IntoSystemConfigs,
+        system::{Commands, Query, Res},
+    },
+    prelude::{Deref, DerefMut},
+    reflect::Reflect,
+};
+
+use crate::{
+    error::TerminalError, transform::TerminalSystemsUpdateTransform, Terminal, TerminalBorder,
+};
+
+use super::TerminalSnapshot;
+
+pub(crate) struct TerminalSnapshotPlugin;
+
+impl Plugin for TerminalSnapshotPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_asset::<TerminalSnapshot>()
+            .register_asset_loader(TerminalSnapshotLoader)
+            .add_systems(
+                PostUpdate,
+                (on_handle_changed, on_asset_event)
+                    .chain()
+                    .before(TerminalSystemsUpdateTransform),
+            );
+    }
+}
+
+/// An asset loader for terminal snapshot files with the `.bats` extension.
+#[derive(Default)]
+pub struct TerminalSnapshotLoader;
+
+impl AssetLoader for TerminalSnapshotLoader {
+    type Asset = TerminalSnapshot;
+    type Settings = ();
+    type Error = TerminalError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        TerminalSnapshot::read(&mut bytes.as_slice())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bats"]
+    }
+}
+
+/// A component which keeps a terminal in sync with a loaded [TerminalSnapshot]
+/// asset.
+///
+/// The terminal will be rebuilt from the snapshot once it finishes loading and
+/// again any time the asset is modified. The terminal's [TerminalBorder] will
+/// be replaced by the snapshot's border, or removed if the snapshot doesn't
+/// have one.
+#[derive(Default, Component, Clone, Debug, Deref, DerefMut, Reflect, PartialEq, Eq)]
+pub struct TerminalSnapshotHandle(pub Handle<TerminalSnapshot>);
+
+fn apply_snapshot(
+    entity: Entity,
+    term: &mut Terminal,
+    snapshot: &TerminalSnapshot,
+    commands: &mut Commands,
+) {
+    *term = snapshot.terminal.clone();
+    match &snapshot.border {
+        Some(border) => commands.entity(entity).insert(border.clone()),
+        None => commands.entity(entity).remove::<TerminalBorder>(),
+    };
+}
+
+fn on_handle_changed(
+    mut q_term: Query<
+        (Entity, &mut Terminal, &TerminalSnapshotHandle),
+        Changed<TerminalSnapshotHandle>,
+    >,
+    assets: Res<Assets<TerminalSnapshot>>,
+    mut commands: Commands,
+) {
+    for (entity, mut term, handle) in &mut q_term {
+        // If the asset isn't loaded yet it will be applied in `on_asset_event`.
+        if let Some(snapshot) = assets.get(&handle.0) {
+            apply_snapshot(entity, &mut term, snapshot, &mut commands);
+        }
+    }
+}
+
+fn on_asset_event(
+    mut q_term: Query<(Entity, &mut Terminal, &TerminalSnapshotHandle)>,
+    mut evt: EventReader<AssetEvent<TerminalSnapshot>>,
+    assets: Res<Assets<TerminalSnapshot>>,
+    mut commands: Commands,
+) {
+    for evt in evt.read() {
+        let id = match evt {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => id,
+            _ => continue,
+        };
+        let Some(snapshot) = assets.get(*id) else {
+            continue;
+        };
+        for (entity, mut term, handle) in &mut q_term {
+            if handle.id() == *id {
+                apply_snapshot(entity, &mut term, snapshot, &mut commands);
+            }
+        }
+    }
+}
@@ -0,0 +1,507 @@
+//! Reading and writing of the native terminal snapshot format. Snapshot files
+//! use the `.bats` extension.
+//!
+//! All values are stored in little endian order. Chars are stored as their
+//! `u32` scalar value and colors as four `f32` linear rgba components.
+//!
+//! | Field        | Type                                        |
+//! |--------------|---------------------------------------------|
+//! | Magic        | `b"BATS"`                                   |
+//! | Version      | `u16`                                       |
+//! | Flags        | `u8`, bit 0 is set if a border is included  |
+//! | Size         | `u32` width, `u32` height                   |
+//! | Clear tile   | glyph, fg color, bg color                   |
+//! | Palette      | `u32` count, followed by each color         |
+//! | Tile runs    | `u32` count, followed by each run as a `u32` tile count, glyph, `u32` fg palette index and `u32` bg palette index |
+//! | Border       | Optional, see [TerminalSnapshot::write]      |
+
+use std::io::{Read, Write};
+
+use bevy::{asset::Asset, color::LinearRgba, math::UVec2, reflect::TypePath};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use enum_ordinalize::Ordinalize;
+
+use crate::{
+    border::{BorderSide, BorderString},
+    compact::{CompactTiles, TileRun},
+    string::{
+        ControlCharacters, HorizontalAlignment, StringDecoration, StringFormatting, StringOverflow,
+        VerticalAlignment,
+    },
+    Terminal, TerminalBorder, TerminalError, Tile,
+};
+
+const MAGIC: [u8; 4] = *b"BATS";
+const FLAG_BORDER: u8 = 1;
+
+/// The current version of the snapshot format. Snapshots written with a newer
+/// version than this can't be read.
+///
+/// The version is bumped at most once per release, when the layout changed
+/// since the previous release.
+pub const SNAPSHOT_VERSION: u16 = 1;
+
+/// A snapshot of a terminal and it's optional border which can be written to
+/// and read from the native binary format.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ascii_terminal::{snapshot::TerminalSnapshot, *};
+///
+/// let terminal = Terminal::new([10, 3]).with_string([0, 0], "Hello");
+/// let snapshot = TerminalSnapshot::new(terminal, Some(TerminalBorder::single_line()));
+/// let mut bytes = Vec::new();
+/// snapshot.write(&mut bytes).unwrap();
+///
+/// let loaded = TerminalSnapshot::read(&mut bytes.as_slice()).unwrap();
+/// assert_eq!(loaded.terminal.tiles(), snapshot.terminal.tiles());
+/// ```
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct TerminalSnapshot {
+    pub terminal: Terminal,
+    pub border: Option<TerminalBorder>,
+}
+
+impl TerminalSnapshot {
+    pub fn new(terminal: Terminal, border: Option<TerminalBorder>) -> Self {
+        Self { terminal, border }
+    }
+
+    /// Read a snapshot from a file on disk.
+    pub fn from_file(file_path: impl AsRef<str>) -> Result<Self, TerminalError> {
+        let mut file = std::fs::File::open(file_path.as_ref())?;
+        Self::read(&mut file)
+    }
+
+    /// Write the snapshot to a file on disk, overwriting it if it exists.
+    pub fn save_to_file(&self, file_path: impl AsRef<str>) -> Result<(), TerminalError> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(file_path.as_ref())?);
+        self.write(&mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Read a snapshot from a stream.
+    pub fn read<R: Read>(r: &mut R) -> Result<Self, TerminalError> {
+        let mut magic = [0; 4];
+        r.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(TerminalError::InvalidFormat(
+                "Not a terminal snapshot".to_string(),
+            ));
+        }
+        let version = r.read_u16::<LittleEndian>()?;
+        if version > SNAPSHOT_VERSION {
+            return Err(TerminalError::InvalidFormat(format!(
+                "Unsupported snapshot version {}",
+                version
+            )));
+        }
+        let flags = r.read_u8()?;
+
+        let size = UVec2::new(r.read_u32::<LittleEndian>()?, r.read_u32::<LittleEndian>()?);
+        let clear_tile = Tile::new(read_char(r)?, read_color(r)?, read_color(r)?);
+
+        let palette_len = r.read_u32::<LittleEndian>()?;
+        let palette = (0..palette_len)
+            .map(|_| read_color(r))
+            .collect::<Result<_, _>>()?;
+        let run_count = r.read_u32::<LittleEndian>()?;
+        let runs = (0..run_count)
+            .map(|_| {
+                Ok(TileRun {
+                    count: r.read_u32::<LittleEndian>()?,
+                    glyph: read_char(r)?,
+                    fg: r.read_u32::<LittleEndian>()?,
+                    bg: r.read_u32::<LittleEndian>()?,
+                })
+            })
+            .collect::<Result<_, TerminalError>>()?;
+
+        let compact = CompactTiles { palette, runs };
+        // Sizes are untrusted, so check them before allocating the terminal
+        compact.check_size(size)?;
+        let mut terminal = Terminal::new(size).with_clear_tile(clear_tile);
+        compact.decode_into(terminal.tiles_mut())?;
+
+        let border = if flags & FLAG_BORDER != 0 {
+            Some(read_border(r)?)
+        } else {
+            None
+        };
+
+        Ok(Self { terminal, border })
+    }
+
+    /// Write the snapshot to a stream.
+    ///
+    /// If the snapshot has a border it's written after the tile data: the
+    /// eight edge glyphs, each as a `u8` flag followed by the glyph if the flag
+    /// is set, then a `u32` count of border strings. Each border string is
+    /// written as a `u8` [BorderSide], `f32` alignment, `i32` offset, `u32` byte
+    /// length followed by the utf8 string, then it's decoration and formatting.
+    /// Formatting is written as a `u8` set of flags where bit 0 is
+    /// `ignore_spaces` and bit 1 is `word_wrap`, followed by a `u8` horizontal
+    /// and `u8` vertical alignment where 0 means no alignment, an optional `u32`
+    /// max line count, a `u8` [StringOverflow], a `u32` tab width and a `u8`
+    /// [ControlCharacters].
+    pub fn write<W: Write>(&self, w: &mut W) -> Result<(), TerminalError> {
+        let term = &self.terminal;
+        w.write_all(&MAGIC)?;
+        w.write_u16::<LittleEndian>(SNAPSHOT_VERSION)?;
+        let flags = if self.border.is_some() {
+            FLAG_BORDER
+        } else {
+            0
+        };
+        w.write_u8(flags)?;
+
+        w.write_u32::<LittleEndian>(term.size().x)?;
+        w.write_u32::<LittleEndian>(term.size().y)?;
+        write_tile(w, &term.clear_tile())?;
+
+        let compact = CompactTiles::encode(term.tiles());
+        w.write_u32::<LittleEndian>(compact.palette.len() as u32)?;
+        for color in &compact.palette {
+            write_color(w, *color)?;
+        }
+        w.write_u32::<LittleEndian>(compact.runs.len() as u32)?;
+        for run in &compact.runs {
+            w.write_u32::<LittleEndian>(run.count)?;
+            w.write_u32::<LittleEndian>(run.glyph as u32)?;
+            w.write_u32::<LittleEndian>(run.fg)?;
+            w.write_u32::<LittleEndian>(run.bg)?;
+        }
+
+        if let Some(border) = &self.border {
+            write_border(w, border)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_char<R: Read>(r: &mut R) -> Result<char, TerminalError> {
+    let value = r.read_u32::<LittleEndian>()?;
+    char::from_u32(value)
+        .ok_or_else(|| TerminalError::InvalidFormat(format!("Invalid char value {}", value)))
+}
+
+fn read_color<R: Read>(r: &mut R) -> Result<LinearRgba, TerminalError> {
+    Ok(LinearRgba::new(
+        r.read_f32::<LittleEndian>()?,
+        r.read_f32::<LittleEndian>()?,
+        r.read_f32::<LittleEndian>()?,
+        r.read_f32::<LittleEndian>()?,
+    ))
+}
+
+fn read_optional<R: Read, T>(
+    r: &mut R,
+    read: impl FnOnce(&mut R) -> Result<T, TerminalError>,
+) -> Result<Option<T>, TerminalError> {
+    match r.read_u8()? {
+        0 => Ok(None),
+        _ => read(r).map(Some),
+    }
+}
+
+fn read_border<R: Read>(r: &mut R) -> Result<TerminalBorder, TerminalError> {
+    let mut border = TerminalBorder::default();
+    for glyph in border.edge_glyphs.iter_mut() {
+        *glyph = read_optional(r, read_char)?;
+    }
+    let string_count = r.read_u32::<LittleEndian>()?;
+    for _ in 0..string_count {
+        let edge = BorderSide::from_ordinal(r.read_u8()? as i8)
+            .ok_or_else(|| TerminalError::InvalidFormat("Invalid border side".to_string()))?;
+        let alignment = r.read_f32::<LittleEndian>()?;
+        let offset = r.read_i32::<LittleEndian>()?;
+        let len = r.read_u32::<LittleEndian>()?;
+        // Read through `take` so a bad length can't allocate more than the
+        // stream contains
+        let mut bytes = Vec::new();
+        r.take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len as usize {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        let string = String::from_utf8(bytes)
+            .map_err(|e| TerminalError::InvalidFormat(format!("Invalid border string: {}", e)))?;
+        let decoration = StringDecoration {
+            fg_color: read_optional(r, read_color)?,
+            bg_color: read_optional(r, read_color)?,
+            delimiters: (read_optional(r, read_char)?, read_optional(r, read_char)?),
+            clear_colors: r.read_u8()? != 0,
+        };
+        let formatting = read_formatting(r)?;
+        border.border_strings.push(BorderString {
+            edge,
+            string,
+            decoration,
+            formatting,
+            offset,
+            alignment,
+        });
+    }
+    Ok(border)
+}
+
+fn write_char<W: Write>(w: &mut W, ch: char) -> std::io::Result<()> {
+    w.write_u32::<LittleEndian>(ch as u32)
+}
+
+fn write_color<W: Write>(w: &mut W, color: LinearRgba) -> std::io::Result<()> {
+    w.write_f32::<LittleEndian>(color.red)?;
+    w.write_f32::<LittleEndian>(color.green)?;
+    w.write_f32::<LittleEndian>(color.blue)?;
+    w.write_f32::<LittleEndian>(color.alpha)
+}
+
+fn write_tile<W: Write>(w: &mut W, tile: &Tile) -> std::io::Result<()> {
+    write_char(w, tile.glyph)?;
+    write_color(w, tile.fg_color)?;
+    write_color(w, tile.bg_color)
+}
+
+fn write_optional<W: Write, T: Copy>(
+    w: &mut W,
+    value: Option<T>,
+    write: impl FnOnce(&mut W, T) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    match value {
+        Some(value) => {
+            w.write_u8(1)?;
+            write(w, value)
+        }
+        None => w.write_u8(0),
+    }
+}
+
+fn read_formatting<R: Read>(r: &mut R) -> Result<StringFormatting, TerminalError> {
+    let flags = r.read_u8()?;
+    let horizontal_alignment = match r.read_u8()? {
+        0 => None,
+        1 => Some(HorizontalAlignment::Left),
+        2 => Some(HorizontalAlignment::Center),
+        3 => Some(HorizontalAlignment::Right),
+        4 => Some(HorizontalAlignment::Justify),
+        v => {
+            return Err(TerminalError::InvalidFormat(format!(
+                "Invalid horizontal alignment {}",
+                v
+            )))
+        }
+    };
+    let vertical_alignment = match r.read_u8()? {
+        0 => None,
+        1 => Some(VerticalAlignment::Top),
+        2 => Some(VerticalAlignment::Center),
+        3 => Some(VerticalAlignment::Bottom),
+        v => {
+            return Err(TerminalError::InvalidFormat(format!(
+                "Invalid vertical alignment {}",
+                v
+            )))
+        }
+    };
+    let max_lines = read_optional(r, |r| Ok(r.read_u32::<LittleEndian>()? as usize))?;
+    let overflow = match r.read_u8()? {
+        0 => StringOverflow::Clip,
+        1 => StringOverflow::Ellipsis,
+        2 => StringOverflow::TruncateMiddle,
+        v => {
+            return Err(TerminalError::InvalidFormat(format!(
+                "Invalid string overflow {}",
+                v
+            )))
+        }
+    };
+    let tab_width = r.read_u32::<LittleEndian>()? as usize;
+    let control_characters = match r.read_u8()? {
+        0 => ControlCharacters::Interpret,
+        1 => ControlCharacters::Symbols,
+        v => {
+            return Err(TerminalError::InvalidFormat(format!(
+                "Invalid control characters {}",
+                v
+            )))
+        }
+    };
+    Ok(StringFormatting {
+        ignore_spaces: flags & 1 != 0,
+        word_wrap: flags & 2 != 0,
+        horizontal_alignment,
+        vertical_alignment,
+        max_lines,
+        overflow,
+        tab_width,
+        control_characters,
+    })
+}
+
+fn write_formatting<W: Write>(w: &mut W, f: &StringFormatting) -> std::io::Result<()> {
+    w.write_u8(f.ignore_spaces as u8 | (f.word_wrap as u8) << 1)?;
+    w.write_u8(match f.horizontal_alignment {
+        None => 0,
+        Some(HorizontalAlignment::Left) => 1,
+        Some(HorizontalAlignment::Center) => 2,
+        Some(HorizontalAlignment::Right) => 3,
+        Some(HorizontalAlignment::Justify) => 4,
+    })?;
+    w.write_u8(match f.vertical_alignment {
+        None => 0,
+        Some(VerticalAlignment::Top) => 1,
+        Some(VerticalAlignment::Center) => 2,
+        Some(VerticalAlignment::Bottom) => 3,
+    })?;
+    write_optional(w, f.max_lines, |w, n| w.write_u32::<LittleEndian>(n as u32))?;
+    w.write_u8(match f.overflow {
+        StringOverflow::Clip => 0,
+        StringOverflow::Ellipsis => 1,
+        StringOverflow::TruncateMiddle => 2,
+    })?;
+    w.write_u32::<LittleEndian>(f.tab_width as u32)?;
+    w.write_u8(match f.control_characters {
+        ControlCharacters::Interpret => 0,
+        ControlCharacters::Symbols => 1,
+    })
+}
+
+fn write_border<W: Write>(w: &mut W, border: &TerminalBorder) -> std::io::Result<()> {
+    for glyph in border.edge_glyphs {
+        write_optional(w, glyph, write_char)?;
+    }
+    w.write_u32::<LittleEndian>(border.border_strings.len() as u32)?;
+    for s in &border.border_strings {
+        w.write_u8(s.edge.ordinal() as u8)?;
+        w.write_f32::<LittleEndian>(s.alignment)?;
+        w.write_i32::<LittleEndian>(s.offset)?;
+        w.write_u32::<LittleEndian>(s.string.len() as u32)?;
+        w.write_all(s.string.as_bytes())?;
+        let d = &s.decoration;
+        write_optional(w, d.fg_color, write_color)?;
+        write_optional(w, d.bg_color, write_color)?;
+        write_optional(w, d.delimiters.0, write_char)?;
+        write_optional(w, d.delimiters.1, write_char)?;
+        w.write_u8(d.clear_colors as u8)?;
+        write_formatting(w, &s.formatting)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color, string::StringDecorator};
+
+    #[test]
+    fn round_trip() {
+        let terminal = Terminal::new([20, 5])
+            .with_clear_tile(Tile::new('.', color::GRAY, color::NAVY))
+            .with_string([0, 0], "Hello, snapshot!".fg(color::RED));
+        let mut border = TerminalBorder::double_line();
+        border.put_string(
+            BorderSide::Bottom,
+            1.0,
+            0,
+            "[Save]"
+                .fg(color::GREEN)
+                .align(HorizontalAlignment::Right)
+                .max_lines(1)
+                .overflow(StringOverflow::Ellipsis)
+                .tab_width(2)
+                .control_characters(ControlCharacters::Symbols),
+        );
+        border.set_edge_glyph(crate::Pivot::TopCenter, None);
+        let snapshot = TerminalSnapshot::new(terminal, Some(border));
+
+        let mut bytes = Vec::new();
+        snapshot.write(&mut bytes).unwrap();
+        let loaded = TerminalSnapshot::read(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!(snapshot.terminal.size(), loaded.terminal.size());
+        assert_eq!(snapshot.terminal.clear_tile(), loaded.terminal.clear_tile());
+        assert_eq!(snapshot.terminal.tiles(), loaded.terminal.tiles());
+
+        let (a, b) = (snapshot.border.unwrap(), loaded.border.unwrap());
+        assert_eq!(a.edge_glyphs, b.edge_glyphs);
+        assert_eq!(1, b.border_strings.len());
+        assert_eq!("[Save]", b.border_strings[0].string);
+        assert_eq!(BorderSide::Bottom, b.border_strings[0].edge);
+        assert_eq!(Some(color::GREEN), b.border_strings[0].decoration.fg_color);
+        assert_eq!(
+            Some(HorizontalAlignment::Right),
+            b.border_strings[0].formatting.horizontal_alignment
+        );
+        assert_eq!(Some(1), b.border_strings[0].formatting.max_lines);
+        assert_eq!(
+            StringOverflow::Ellipsis,
+            b.border_strings[0].formatting.overflow
+        );
+        assert_eq!(2, b.border_strings[0].formatting.tab_width);
+        assert_eq!(
+            ControlCharacters::Symbols,
+            b.border_strings[0].formatting.control_characters
+        );
+    }
+
+    #[test]
+    fn invalid_data() {
+        assert!(matches!(
+            TerminalSnapshot::read(&mut b"NOPE".as_slice()),
+            Err(TerminalError::InvalidFormat(_))
+        ));
+
+        let snapshot = TerminalSnapshot::new(Terminal::new([4, 4]), None);
+        let mut bytes = Vec::new();
+        snapshot.write(&mut bytes).unwrap();
+        bytes[4] = 0xFF;
+        assert!(matches!(
+            TerminalSnapshot::read(&mut bytes.as_slice()),
+            Err(TerminalError::InvalidFormat(_))
+        ));
+
+        bytes[4] = 1;
+        let mut huge = bytes.clone();
+        huge[7..11].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            TerminalSnapshot::read(&mut huge.as_slice()),
+            Err(TerminalError::InvalidFormat(_))
+        ));
+
+        // Two runs at the end of the data, each 16 bytes starting with the count
+        let mut term = Terminal::new([4, 4]);
+        term.put_char([0, 0], 'a');
+        let mut overflow = Vec::new();
+        TerminalSnapshot::new(term, None)
+            .write(&mut overflow)
+            .unwrap();
+        let end = overflow.len();
+        for i in [end - 32, end - 16] {
+            overflow[i..i + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        }
+        assert!(matches!(
+            TerminalSnapshot::read(&mut overflow.as_slice()),
+            Err(TerminalError::InvalidFormat(_))
+        ));
+
+        let mut border = TerminalBorder::single_line();
+        border.put_string(BorderSide::Top, 0.0, 0, "Title");
+        let mut long_string = Vec::new();
+        TerminalSnapshot::new(Terminal::new([8, 8]), Some(border))
+            .write(&mut long_string)
+            .unwrap();
+        let i = long_string.windows(5).position(|w| w == b"Title").unwrap();
+        long_string[i - 4..i].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            TerminalSnapshot::read(&mut long_string.as_slice()),
+            Err(TerminalError::Io(_))
+        ));
+
+        bytes.truncate(12);
+        assert!(matches!(
+            TerminalSnapshot::read(&mut bytes.as_slice()),
+            Err(TerminalError::Io(_))
+        ));
+    }
+}
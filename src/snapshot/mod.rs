@@ -0,0 +1,9 @@
+//! A compact, versioned binary format for saving and loading terminal
+//! snapshots, either directly or through bevy's asset server.
+mod asset;
+pub mod format;
+
+pub use asset::{TerminalSnapshotHandle, TerminalSnapshotLoader};
+pub use format::TerminalSnapshot;
+
+pub(crate) use asset::TerminalSnapshotPlugin;
@@ -1,10 +1,16 @@
 //! Utilities  for writing formatted/decorated strings to the terminal
 //! without any extra allocations.
-use std::{ops::Sub, str::Chars};
+use std::{iter::Chain, ops::Range, str::Chars};
 
-use bevy::{color::LinearRgba, math::IVec2, reflect::Reflect};
+use bevy::{
+    color::LinearRgba,
+    math::{IVec2, UVec2},
+    reflect::Reflect,
+};
 use sark_grids::{GridRect, GridSize, Pivot, PivotedPoint};
 
+use crate::ascii;
+
 /// A string with optional [StringDecoration] and [StringFormatting] applied.
 ///
 /// `dont_word_wrap` Can be used to disable word wrapping, which is enabled by
@@ -54,10 +60,41 @@ impl<T: AsRef<str>> TerminalString<T> {
         self.formatting.word_wrap = false;
         self
     }
+
+    pub fn align(mut self, alignment: HorizontalAlignment) -> Self {
+        self.formatting.horizontal_alignment = Some(alignment);
+        self
+    }
+
+    pub fn align_vertical(mut self, alignment: VerticalAlignment) -> Self {
+        self.formatting.vertical_alignment = Some(alignment);
+        self
+    }
+
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.formatting.max_lines = Some(max_lines);
+        self
+    }
+
+    pub fn overflow(mut self, overflow: StringOverflow) -> Self {
+        self.formatting.overflow = overflow;
+        self
+    }
+
+    pub fn tab_width(mut self, tab_width: usize) -> Self {
+        self.formatting.tab_width = tab_width;
+        self
+    }
+
+    pub fn control_characters(mut self, control_characters: ControlCharacters) -> Self {
+        self.formatting.control_characters = control_characters;
+        self
+    }
 }
 
 /// Optional decoration to be applied to a string being written to a terminal.
 #[derive(Default, Debug, Clone, Copy, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StringDecoration {
     /// An optional foreground color for the string. If set to None then the
     /// terminal's clear tile color will be used.
@@ -176,6 +213,30 @@ impl<T: AsRef<str>> DecoratedString<T> {
             },
         }
     }
+
+    pub fn align(self, alignment: HorizontalAlignment) -> TerminalString<T> {
+        TerminalString::from(self).align(alignment)
+    }
+
+    pub fn align_vertical(self, alignment: VerticalAlignment) -> TerminalString<T> {
+        TerminalString::from(self).align_vertical(alignment)
+    }
+
+    pub fn max_lines(self, max_lines: usize) -> TerminalString<T> {
+        TerminalString::from(self).max_lines(max_lines)
+    }
+
+    pub fn overflow(self, overflow: StringOverflow) -> TerminalString<T> {
+        TerminalString::from(self).overflow(overflow)
+    }
+
+    pub fn tab_width(self, tab_width: usize) -> TerminalString<T> {
+        TerminalString::from(self).tab_width(tab_width)
+    }
+
+    pub fn control_characters(self, control_characters: ControlCharacters) -> TerminalString<T> {
+        TerminalString::from(self).control_characters(control_characters)
+    }
 }
 
 impl<T: AsRef<str>> From<T> for DecoratedString<T> {
@@ -189,6 +250,7 @@ impl<T: AsRef<str>> From<T> for DecoratedString<T> {
 
 /// Optional formatting to be applied to a string being written to a terminal.
 #[derive(Debug, Clone, Reflect, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StringFormatting {
     /// Defines whether or not 'empty' (" ") tiles will be modified when writing
     /// strings to the terminal. If set to false then decorations will be
@@ -201,6 +263,92 @@ pub struct StringFormatting {
     ///
     /// Defaults to true.
     pub word_wrap: bool,
+    /// How each line is placed horizontally within the area the string is
+    /// written to. If set, the pivot and x position of the string are ignored.
+    ///
+    /// Defaults to None.
+    pub horizontal_alignment: Option<HorizontalAlignment>,
+    /// How the lines are placed vertically within the area the string is
+    /// written to. If set, the pivot and y position of the string are ignored.
+    ///
+    /// Defaults to None.
+    pub vertical_alignment: Option<VerticalAlignment>,
+    /// The maximum number of lines to write. The string will also be limited
+    /// to the lines that fit in the area it's written to.
+    ///
+    /// Defaults to None.
+    pub max_lines: Option<usize>,
+    /// How the last line is written when the string doesn't fit.
+    ///
+    /// Defaults to [StringOverflow::Clip].
+    pub overflow: StringOverflow,
+    /// The distance in tiles between tab stops. A tab moves to the next tab
+    /// stop, measured from the start of the line.
+    ///
+    /// Defaults to 4.
+    pub tab_width: usize,
+    /// How control characters such as tabs and carriage returns are written.
+    ///
+    /// Defaults to [ControlCharacters::Interpret].
+    pub control_characters: ControlCharacters,
+}
+
+/// How control characters in a string are written to a terminal.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ControlCharacters {
+    /// Tabs move to the next tab stop and carriage returns move back to the
+    /// start of the line. Any other control characters are skipped.
+    #[default]
+    Interpret,
+    /// Control characters are written as their CP437 symbols, for example
+    /// '\x01' is written as '☺'. Newlines still start a new line.
+    Symbols,
+}
+
+/// How a string is cut off when it has more lines than fit in the area it's
+/// written to, or than [StringFormatting::max_lines].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StringOverflow {
+    /// Stop after the last line that fits.
+    #[default]
+    Clip,
+    /// End the last line with an ellipsis.
+    Ellipsis,
+    /// Replace the middle of the remaining string with an ellipsis so the end
+    /// of the string is still visible. Useful for file paths.
+    TruncateMiddle,
+}
+
+/// The glyph used to show truncated text. This is '…' if the font has a glyph
+/// for it, otherwise "...".
+pub fn ellipsis() -> &'static str {
+    match ascii::char_to_index('…') {
+        Some(_) => "…",
+        None => "...",
+    }
+}
+
+/// Horizontal alignment of the lines of a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HorizontalAlignment {
+    Left,
+    Center,
+    Right,
+    /// Spaces are distributed across each line so it fills the whole width.
+    /// The last line, and lines ending in a newline, are left aligned.
+    Justify,
+}
+
+/// Vertical alignment of the lines of a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerticalAlignment {
+    Top,
+    Center,
+    Bottom,
 }
 
 impl StringFormatting {
@@ -210,6 +358,45 @@ impl StringFormatting {
             ..Self::default()
         }
     }
+
+    /// The column after writing a character at the given column of a line.
+    fn advance(&self, column: usize, ch: char) -> usize {
+        if self.control_characters == ControlCharacters::Symbols {
+            return column + 1;
+        }
+        match ch {
+            '\t' => {
+                let tab = self.tab_width.max(1);
+                (column / tab + 1) * tab
+            }
+            '\r' => 0,
+            c if c.is_control() => column,
+            _ => column + 1,
+        }
+    }
+
+    /// The number of tiles a line will take up when written.
+    fn line_width(&self, line: &str) -> usize {
+        let mut column = 0;
+        let mut width = 0;
+        for ch in line.chars() {
+            column = self.advance(column, ch);
+            width = width.max(column);
+        }
+        width
+    }
+
+    /// The longest start of a string that fits in the given width.
+    fn take_width<'a>(&self, string: &'a str, width: usize) -> &'a str {
+        let mut column = 0;
+        for (i, ch) in string.char_indices() {
+            column = self.advance(column, ch);
+            if column > width {
+                return &string[..i];
+            }
+        }
+        string
+    }
 }
 
 impl Default for StringFormatting {
@@ -217,6 +404,12 @@ impl Default for StringFormatting {
         Self {
             ignore_spaces: Default::default(),
             word_wrap: true,
+            horizontal_alignment: None,
+            vertical_alignment: None,
+            max_lines: None,
+            overflow: StringOverflow::Clip,
+            tab_width: 4,
+            control_characters: ControlCharacters::Interpret,
         }
     }
 }
@@ -230,6 +423,19 @@ pub struct FormattedString<T: AsRef<str>> {
 pub trait StringFormatter<T: AsRef<str>> {
     fn ignore_spaces(self) -> FormattedString<T>;
     fn dont_word_wrap(self) -> FormattedString<T>;
+    /// Align each line of the string horizontally within the area it's
+    /// written to.
+    fn align(self, alignment: HorizontalAlignment) -> FormattedString<T>;
+    /// Align the string vertically within the area it's written to.
+    fn align_vertical(self, alignment: VerticalAlignment) -> FormattedString<T>;
+    /// Limit the number of lines the string can be wrapped to.
+    fn max_lines(self, max_lines: usize) -> FormattedString<T>;
+    /// Set how the string is cut off when it doesn't fit.
+    fn overflow(self, overflow: StringOverflow) -> FormattedString<T>;
+    /// Set the distance in tiles between tab stops.
+    fn tab_width(self, tab_width: usize) -> FormattedString<T>;
+    /// Set how control characters are written.
+    fn control_characters(self, control_characters: ControlCharacters) -> FormattedString<T>;
 }
 
 impl<T: AsRef<str>> StringFormatter<T> for T {
@@ -252,6 +458,66 @@ impl<T: AsRef<str>> StringFormatter<T> for T {
             },
         }
     }
+
+    fn align(self, alignment: HorizontalAlignment) -> FormattedString<T> {
+        FormattedString {
+            string: self,
+            formatting: StringFormatting {
+                horizontal_alignment: Some(alignment),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn align_vertical(self, alignment: VerticalAlignment) -> FormattedString<T> {
+        FormattedString {
+            string: self,
+            formatting: StringFormatting {
+                vertical_alignment: Some(alignment),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn max_lines(self, max_lines: usize) -> FormattedString<T> {
+        FormattedString {
+            string: self,
+            formatting: StringFormatting {
+                max_lines: Some(max_lines),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn overflow(self, overflow: StringOverflow) -> FormattedString<T> {
+        FormattedString {
+            string: self,
+            formatting: StringFormatting {
+                overflow,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn tab_width(self, tab_width: usize) -> FormattedString<T> {
+        FormattedString {
+            string: self,
+            formatting: StringFormatting {
+                tab_width,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn control_characters(self, control_characters: ControlCharacters) -> FormattedString<T> {
+        FormattedString {
+            string: self,
+            formatting: StringFormatting {
+                control_characters,
+                ..Default::default()
+            },
+        }
+    }
 }
 
 impl<T: AsRef<str>> StringFormatter<T> for FormattedString<T> {
@@ -264,6 +530,36 @@ impl<T: AsRef<str>> StringFormatter<T> for FormattedString<T> {
         self.formatting.word_wrap = false;
         self
     }
+
+    fn align(mut self, alignment: HorizontalAlignment) -> FormattedString<T> {
+        self.formatting.horizontal_alignment = Some(alignment);
+        self
+    }
+
+    fn align_vertical(mut self, alignment: VerticalAlignment) -> FormattedString<T> {
+        self.formatting.vertical_alignment = Some(alignment);
+        self
+    }
+
+    fn max_lines(mut self, max_lines: usize) -> FormattedString<T> {
+        self.formatting.max_lines = Some(max_lines);
+        self
+    }
+
+    fn overflow(mut self, overflow: StringOverflow) -> FormattedString<T> {
+        self.formatting.overflow = overflow;
+        self
+    }
+
+    fn tab_width(mut self, tab_width: usize) -> FormattedString<T> {
+        self.formatting.tab_width = tab_width;
+        self
+    }
+
+    fn control_characters(mut self, control_characters: ControlCharacters) -> FormattedString<T> {
+        self.formatting.control_characters = control_characters;
+        self
+    }
 }
 
 impl<T: AsRef<str>> From<DecoratedString<T>> for TerminalString<T> {
@@ -342,16 +638,121 @@ impl<T: AsRef<str> + Default> From<T> for TerminalString<T> {
 }
 
 /// Precalculate the number of vertical lines a wrapped string will occupy.
-// TODO: Integrate with `wrap_string` to avoid the duplicate work
-fn line_count(mut input: &str, max_len: usize, wrap: bool) -> usize {
+pub(crate) fn line_count(input: &str, max_len: usize, wrap: bool) -> usize {
+    let formatting = StringFormatting {
+        word_wrap: wrap,
+        ..Default::default()
+    };
+    count_lines(input, max_len, &formatting)
+}
+
+/// Precalculate the number of vertical lines a formatted string will occupy.
+// TODO: Integrate with `wrap_line` to avoid the duplicate work
+fn count_lines(mut input: &str, max_len: usize, formatting: &StringFormatting) -> usize {
     let mut line_count = 0;
-    while let Some((_, rem)) = wrap_string(input, max_len, wrap) {
+    while let Some((_, rem)) = wrap_line(input, max_len, formatting) {
         line_count += 1;
         input = rem;
     }
     line_count
 }
 
+/// The area a string will occupy when written to a terminal, as calculated by
+/// [TerminalString::measure] or [measure_string].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StringMeasurement {
+    /// The width in tiles of the longest line.
+    pub width: usize,
+    /// The number of lines.
+    pub height: usize,
+    /// Each line the string will be split into, from top to bottom.
+    pub lines: Vec<LineSpan>,
+}
+
+impl StringMeasurement {
+    /// The width and height of the string in tiles.
+    pub fn size(&self) -> UVec2 {
+        UVec2::new(self.width as u32, self.height as u32)
+    }
+}
+
+/// A single line of a measured string.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LineSpan {
+    /// The byte range of the line in the original string, excluding any
+    /// delimiters.
+    pub range: Range<usize>,
+    /// The width of the line in tiles, including any delimiters.
+    pub width: usize,
+}
+
+impl<T: AsRef<str>> TerminalString<T> {
+    /// Measure the area the string will occupy when written to a terminal with
+    /// the given maximum line width, respecting word wrap, newlines,
+    /// delimiters and [StringFormatting::max_lines].
+    ///
+    /// Note that delimiters are placed around the wrapped string so they may
+    /// extend the first and last lines past `max_width`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_ascii_terminal::string::*;
+    ///
+    /// let measure = TerminalString::from("Hello world").measure(8);
+    /// assert_eq!([5, 2], [measure.width, measure.height]);
+    /// ```
+    pub fn measure(&self, max_width: usize) -> StringMeasurement {
+        let string = self.string.as_ref();
+        let mut lines = Vec::new();
+        let max_lines = self.formatting.max_lines.unwrap_or(usize::MAX);
+        if max_width > 0 {
+            let mut remaining = string;
+            while let Some((line, rem)) = wrap_line(remaining, max_width, &self.formatting) {
+                if lines.len() == max_lines {
+                    break;
+                }
+                let start = line.as_ptr() as usize - string.as_ptr() as usize;
+                let mut span = LineSpan {
+                    range: start..start + line.len(),
+                    width: self.formatting.line_width(line),
+                };
+                if lines.len() + 1 == max_lines && !rem.is_empty() {
+                    let parts = truncate_line(remaining, line, max_width, &self.formatting);
+                    span.range = start..start + parts[0].len();
+                    span.width = parts_width(&parts, &self.formatting);
+                }
+                lines.push(span);
+                remaining = rem;
+            }
+        }
+        let (open, close) = self.decoration.delimiters;
+        if max_lines > 0 && (open.is_some() || close.is_some()) {
+            if lines.is_empty() {
+                lines.push(LineSpan::default());
+            }
+            lines[0].width += open.is_some() as usize;
+            if let Some(last) = lines.last_mut() {
+                last.width += close.is_some() as usize;
+            }
+        }
+        StringMeasurement {
+            width: lines.iter().map(|l| l.width).max().unwrap_or(0),
+            height: lines.len(),
+            lines,
+        }
+    }
+}
+
+/// Measure the area a string will occupy when written to a terminal with the
+/// given maximum line width. See [TerminalString::measure].
+pub fn measure_string<T: AsRef<str>>(
+    string: impl Into<TerminalString<T>>,
+    max_width: usize,
+) -> StringMeasurement {
+    string.into().measure(max_width)
+}
+
 /// Calculate the number of tiles to offset a string by horizontally based
 /// on it's pivot.
 fn hor_pivot_offset(pivot: Pivot, line_len: usize) -> i32 {
@@ -362,21 +763,149 @@ fn hor_pivot_offset(pivot: Pivot, line_len: usize) -> i32 {
 }
 
 /// Calculate the amount of lines to offset a wrapped string by based on a pivot
-fn ver_pivot_offset(string: &str, pivot: Pivot, max_width: usize, wrap: bool) -> i32 {
+fn ver_pivot_offset(pivot: Pivot, line_count: usize) -> i32 {
     match pivot {
         Pivot::TopLeft | Pivot::TopCenter | Pivot::TopRight => 0,
-        _ => {
-            let line_count = line_count(string, max_width, wrap);
-            (line_count.saturating_sub(1) as f32 * (1.0 - pivot.normalized().y)).round() as i32
+        _ => (line_count.saturating_sub(1) as f32 * (1.0 - pivot.normalized().y)).round() as i32,
+    }
+}
+
+/// The x position of the first tile of an aligned line.
+fn aligned_x(rect: GridRect, alignment: HorizontalAlignment, line_len: usize) -> i32 {
+    let space = rect.width().saturating_sub(line_len) as i32;
+    rect.left()
+        + match alignment {
+            HorizontalAlignment::Left | HorizontalAlignment::Justify => 0,
+            HorizontalAlignment::Center => space / 2,
+            HorizontalAlignment::Right => space,
+        }
+}
+
+/// The y position of the first line of a vertically aligned string.
+fn aligned_y(rect: GridRect, alignment: VerticalAlignment, line_count: usize) -> i32 {
+    let space = rect.height().saturating_sub(line_count) as i32;
+    rect.top()
+        - match alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Center => space / 2,
+            VerticalAlignment::Bottom => space,
+        }
+}
+
+/// Extra spacing added after each space of a justified line.
+#[derive(Debug, Clone, Copy)]
+struct JustifySpacing {
+    extra: usize,
+    gaps: usize,
+    gap: usize,
+}
+
+impl JustifySpacing {
+    /// Calculate the spacing for a line which was wrapped from `source`,
+    /// leaving `remaining`. Returns [None] for the last line of a paragraph.
+    fn new(
+        source: &str,
+        line: &str,
+        remaining: &str,
+        line_width: usize,
+        width: usize,
+    ) -> Option<Self> {
+        let separator = &source[line.len()..source.len() - remaining.len()];
+        if remaining.is_empty() || separator.contains('\n') {
+            return None;
         }
+        let gaps = line.chars().filter(|c| *c == ' ').count();
+        let extra = width.saturating_sub(line_width);
+        (gaps > 0 && extra > 0).then_some(Self {
+            extra,
+            gaps,
+            gap: 0,
+        })
     }
+
+    /// The extra tiles to add after the next space.
+    fn next_gap(&mut self) -> i32 {
+        let gap = self.extra / self.gaps + (self.gap < self.extra % self.gaps) as usize;
+        self.gap += 1;
+        gap as i32
+    }
+}
+
+/// The first `count` characters of a string.
+fn take_chars(string: &str, count: usize) -> &str {
+    let end = string
+        .char_indices()
+        .nth(count)
+        .map_or(string.len(), |(i, _)| i);
+    &string[..end]
+}
+
+/// The longest end of a string that fits in the given width.
+fn last_width<'a>(string: &'a str, width: usize, formatting: &StringFormatting) -> &'a str {
+    let mut tail = "";
+    for (i, _) in string.char_indices().rev() {
+        if formatting.line_width(&string[i..]) > width {
+            break;
+        }
+        tail = &string[i..];
+    }
+    tail
+}
+
+/// Split the last visible line of an overflowing string into the parts to
+/// write: the start of the line, the ellipsis and the end of the string.
+/// `source` is the remaining string starting at the line.
+fn truncate_line<'a>(
+    source: &'a str,
+    line: &'a str,
+    max_len: usize,
+    formatting: &StringFormatting,
+) -> [&'a str; 3] {
+    let ellipsis = take_chars(ellipsis(), max_len);
+    let keep = max_len - ellipsis.chars().count();
+    match formatting.overflow {
+        StringOverflow::Clip => [line, "", ""],
+        StringOverflow::Ellipsis => [formatting.take_width(line, keep).trim_end(), ellipsis, ""],
+        StringOverflow::TruncateMiddle => {
+            let last = source.trim_end().rsplit('\n').next().unwrap_or_default();
+            let tail = last_width(last, keep / 2, formatting);
+            let first = source.split('\n').next().unwrap_or_default();
+            let mut head =
+                formatting.take_width(first, keep.saturating_sub(formatting.line_width(tail)));
+            // Tab stops in the tail depend on the column it starts at
+            while !head.is_empty() && parts_width(&[head, ellipsis, tail], formatting) > max_len {
+                let end = head.char_indices().last().map_or(0, |(i, _)| i);
+                head = &head[..end];
+            }
+            [head, ellipsis, tail]
+        }
+    }
+}
+
+/// The width of a line split by [truncate_line].
+fn parts_width(parts: &[&str; 3], formatting: &StringFormatting) -> usize {
+    let mut column = 0;
+    let mut width = 0;
+    for ch in parts.iter().flat_map(|s| s.chars()) {
+        column = formatting.advance(column, ch);
+        width = width.max(column);
+    }
+    width
 }
 
 /// Wrap a string to fit within a given line length. It will first try to split
 /// at the first newline before max_len, then if word_wrap is true, it will
 /// split at the last whitespace character before max_len, otherwise the string
 /// will be split at max_len.
-fn wrap_string(string: &str, max_len: usize, word_wrap: bool) -> Option<(&str, &str)> {
+///
+/// Lengths are measured in tiles as the line will be written with the given
+/// formatting, so multi-byte glyphs take up a single tile and tabs extend to
+/// the next tab stop.
+fn wrap_line<'a>(
+    string: &'a str,
+    max_len: usize,
+    formatting: &StringFormatting,
+) -> Option<(&'a str, &'a str)> {
     debug_assert!(
         max_len > 0,
         "max_len for wrap_string must be greater than 0"
@@ -385,30 +914,33 @@ fn wrap_string(string: &str, max_len: usize, word_wrap: bool) -> Option<(&str, &
         return None;
     }
 
-    // Handle newlines first
-    if let Some(newline_index) = string.chars().take(max_len).position(|c| c == '\n') {
-        let (a, b) = string.split_at(newline_index);
-        return Some((a.trim_end(), b.trim_start()));
-    };
-
-    let len = string.chars().count();
-    if len <= max_len {
-        return Some((string.trim_end(), ""));
-    };
-
-    let move_back = if word_wrap {
-        string
-            .chars()
-            .rev()
-            .skip(len - max_len - 1)
-            .position(|c| c.is_whitespace())
-            .unwrap_or_default()
-    } else {
-        0
-    };
-
-    let (a, b) = string.split_at(max_len.sub(move_back));
-    Some((a.trim_end(), b.trim_start()))
+    let mut column = 0;
+    let mut last_space = None;
+    for (i, ch) in string.char_indices() {
+        if ch == '\n' {
+            let (a, b) = string.split_at(i);
+            return Some((a.trim_end(), b.trim_start()));
+        }
+        column = formatting.advance(column, ch);
+        if column > max_len {
+            let split = match last_space {
+                _ if ch.is_whitespace() => i,
+                Some(space) if formatting.word_wrap => space,
+                _ => i,
+            };
+            // Always make progress, even if a single character doesn't fit
+            let split = match split {
+                0 => ch.len_utf8(),
+                _ => split,
+            };
+            let (a, b) = string.split_at(split);
+            return Some((a.trim_end(), b.trim_start()));
+        }
+        if ch.is_whitespace() {
+            last_space = Some(i);
+        }
+    }
+    Some((string.trim_end(), ""))
 }
 
 /// An iterator for writing wrapped strings to a rectangular grid. Will attempt
@@ -422,11 +954,22 @@ pub struct StringIter<'a> {
     rect: GridRect,
     xy: IVec2,
     pivot: Pivot,
-    current: Chars<'a>,
+    current: LineChars<'a>,
     formatting: StringFormatting,
     decoration: StringDecoration,
+    justify: Option<JustifySpacing>,
+    lines_left: usize,
+    /// The x position of the start of the current line.
+    line_start: i32,
+    /// The column of the current line being written.
+    column: usize,
+    /// Spaces left to write to reach the next tab stop.
+    tab_fill: usize,
 }
 
+/// The characters of a line, which may be truncated with an ellipsis.
+type LineChars<'a> = Chain<Chain<Chars<'a>, Chars<'a>>, Chars<'a>>;
+
 impl<'a> StringIter<'a> {
     pub fn new(
         string: &'a str,
@@ -449,68 +992,176 @@ impl<'a> StringIter<'a> {
             rect.size
         );
 
-        let first_max_len = rect.width().saturating_sub(local_xy.x as usize);
-        let (first, remaining) =
-            wrap_string(string, first_max_len, formatting.word_wrap).unwrap_or_default();
+        // Aligned lines always use the full width of the rect
+        let first_max_len = match formatting.horizontal_alignment {
+            Some(_) => rect.width(),
+            None => rect.width().saturating_sub(local_xy.x as usize),
+        };
+        let (first, remaining) = wrap_line(string, first_max_len, &formatting).unwrap_or_default();
 
-        let horizontal_offset = hor_pivot_offset(pivot, first.len());
-        let vertical_offset = ver_pivot_offset(string, pivot, rect.width(), formatting.word_wrap);
+        let line_count = count_lines(string, rect.width(), &formatting)
+            .min(formatting.max_lines.unwrap_or(usize::MAX));
 
         let mut xy = rect.pivoted_point(pivoted_point);
+        xy.y = match formatting.vertical_alignment {
+            Some(alignment) => aligned_y(rect, alignment, line_count),
+            None => xy.y + ver_pivot_offset(pivot, line_count),
+        };
+        // Don't write past the bottom of the rect
+        let lines_left = line_count.min((xy.y - rect.bottom() + 1).max(0) as usize);
 
-        xy.x += horizontal_offset;
-        xy.y += vertical_offset;
-
-        Self {
+        let mut iter = Self {
             remaining,
             rect,
             xy,
             pivot,
-            current: first.chars(),
+            current: "".chars().chain("".chars()).chain("".chars()),
             formatting,
             decoration,
+            justify: None,
+            lines_left,
+            line_start: 0,
+            column: 0,
+            tab_fill: 0,
+        };
+        // Nothing fits, ie: `max_lines(0)` or the string starts below the rect
+        if lines_left == 0 && !string.is_empty() {
+            iter.remaining = "";
+            iter.decoration.delimiters = (None, None);
+            return iter;
         }
+        let len = iter.start_line(string, first, remaining, first_max_len);
+        iter.xy.x = match formatting.horizontal_alignment {
+            Some(alignment) => aligned_x(rect, alignment, len),
+            None => xy.x + hor_pivot_offset(pivot, len),
+        };
+        iter.line_start = iter.xy.x;
+        iter
     }
 
     fn line_feed(&mut self, line_len: usize) {
-        let x = self.rect.pivot_point(self.pivot).x;
-        let hor_offset = hor_pivot_offset(self.pivot, line_len);
-        self.xy.x = x + hor_offset;
+        self.xy.x = match self.formatting.horizontal_alignment {
+            Some(alignment) => aligned_x(self.rect, alignment, line_len),
+            None => self.rect.pivot_point(self.pivot).x + hor_pivot_offset(self.pivot, line_len),
+        };
         self.xy.y -= 1;
+        self.line_start = self.xy.x;
     }
-}
 
-impl Iterator for StringIter<'_> {
-    type Item = (IVec2, (char, Option<LinearRgba>, Option<LinearRgba>));
+    /// Begin writing a line which was wrapped from `source`, truncating it if
+    /// it's the last line and the string overflows. Returns the number of
+    /// tiles that will be written for the line.
+    fn start_line(
+        &mut self,
+        source: &'a str,
+        line: &'a str,
+        remaining: &'a str,
+        max_len: usize,
+    ) -> usize {
+        self.remaining = remaining;
+        self.lines_left = self.lines_left.saturating_sub(1);
+        self.column = 0;
+        self.tab_fill = 0;
+        let parts = if self.lines_left == 0 && !remaining.is_empty() {
+            self.justify = None;
+            truncate_line(source, line, max_len, &self.formatting)
+        } else {
+            self.set_justify(source, line, remaining);
+            [line, "", ""]
+        };
+        self.current = parts[0]
+            .chars()
+            .chain(parts[1].chars())
+            .chain(parts[2].chars());
+        parts_width(&parts, &self.formatting)
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let ch = self
-            .decoration
+    fn set_justify(&mut self, source: &str, line: &str, remaining: &str) {
+        self.justify = match self.formatting.horizontal_alignment {
+            Some(HorizontalAlignment::Justify) => {
+                let line_width = self.formatting.line_width(line);
+                JustifySpacing::new(source, line, remaining, line_width, self.rect.width())
+            }
+            _ => None,
+        };
+    }
+
+    /// The next character to write, or [None] if the string is finished.
+    fn next_char(&mut self) -> Option<char> {
+        if self.tab_fill > 0 {
+            self.tab_fill -= 1;
+            return Some(' ');
+        }
+        self.decoration
             .delimiters
             .0
             .take()
             .or_else(|| self.current.next())
             .or_else(|| {
-                let (next_line, remaining) =
-                    wrap_string(self.remaining, self.rect.width(), self.formatting.word_wrap)?;
-
-                self.line_feed(next_line.len());
-                if self.xy.y < 0 {
+                if self.lines_left == 0 {
                     return None;
                 }
-                self.remaining = remaining;
-                self.current = next_line.chars();
+                let width = self.rect.width();
+                let (next_line, remaining) = wrap_line(self.remaining, width, &self.formatting)?;
+                let len = self.start_line(self.remaining, next_line, remaining, width);
+                self.line_feed(len);
                 self.current.next()
             })
-            .or_else(|| self.decoration.delimiters.1.take())?;
-        let p = self.xy;
-        self.xy.x += 1;
-        if ch == ' ' && self.formatting.ignore_spaces {
-            return self.next();
+            .or_else(|| self.decoration.delimiters.1.take())
+    }
+}
+
+impl Iterator for StringIter<'_> {
+    type Item = (IVec2, (char, Option<LinearRgba>, Option<LinearRgba>));
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let filling_tab = self.tab_fill > 0;
+            let raw = self.next_char()?;
+            let interpret = self.formatting.control_characters == ControlCharacters::Interpret;
+            let ch = match raw {
+                '\t' if interpret => {
+                    let stop = self.formatting.advance(self.column, raw);
+                    self.tab_fill = stop.saturating_sub(self.column + 1);
+                    ' '
+                }
+                '\r' if interpret => {
+                    self.xy.x = self.line_start;
+                    self.column = 0;
+                    continue;
+                }
+                c if interpret && c.is_control() => continue,
+                c if c.is_control() => control_symbol(c),
+                c => c,
+            };
+            let p = self.xy;
+            self.xy.x += 1;
+            self.column += 1;
+            if raw == ' ' && !filling_tab {
+                if let Some(justify) = self.justify.as_mut() {
+                    self.xy.x += justify.next_gap();
+                }
+            }
+            if ch == ' ' && self.formatting.ignore_spaces {
+                continue;
+            }
+            // Tab stops and truncated lines can reach past the rect
+            if p.x < self.rect.left() || p.x > self.rect.right() {
+                continue;
+            }
+            let fg = self.decoration.fg_color;
+            let bg = self.decoration.bg_color;
+            return Some((p, (ch, fg, bg)));
         }
-        let fg = self.decoration.fg_color;
-        let bg = self.decoration.bg_color;
-        Some((p, (ch, fg, bg)))
+    }
+}
+
+/// The CP437 symbol for a control character.
+fn control_symbol(ch: char) -> char {
+    match ch as u32 {
+        i @ 0..=31 => ascii::index_to_char(i as u8),
+        127 => ascii::index_to_char(127),
+        _ => ch,
     }
 }
 
@@ -522,6 +1173,14 @@ mod tests {
 
     use super::*;
 
+    fn wrap_string(string: &str, max_len: usize, word_wrap: bool) -> Option<(&str, &str)> {
+        let formatting = StringFormatting {
+            word_wrap,
+            ..Default::default()
+        };
+        wrap_line(string, max_len, &formatting)
+    }
+
     /// Map each character in the string to it's grid position
     fn make_map(string: StringIter<'_>) -> HashMap<[i32; 2], char> {
         string.map(|(p, (ch, _, _))| (p.to_array(), ch)).collect()
@@ -654,10 +1313,13 @@ mod tests {
         let string = "A somewhat longer line\nWith a newline or two\nOkay? WHEEEEEE.";
         let line_len = 12;
         let wrap = true;
-        let offset = ver_pivot_offset(string, Pivot::TopLeft, line_len, wrap);
+        let offset = ver_pivot_offset(Pivot::TopLeft, line_count(string, line_len, wrap));
         assert_eq!(0, offset);
         assert_eq!(7, line_count(string, 12, wrap));
-        assert_eq!(6, ver_pivot_offset(string, Pivot::BottomLeft, 12, wrap));
+        assert_eq!(
+            6,
+            ver_pivot_offset(Pivot::BottomLeft, line_count(string, 12, wrap))
+        );
     }
 
     #[test]
@@ -665,9 +1327,9 @@ mod tests {
         let string = "A somewhat longer line\nWith a newline or two\nOkay? WHEEEEEE.";
         let line_len = 12;
         let wrap = false;
-        let offset = ver_pivot_offset(string, Pivot::TopLeft, line_len, wrap);
+        let offset = ver_pivot_offset(Pivot::TopLeft, line_count(string, line_len, wrap));
         assert_eq!(0, offset);
-        let offset = ver_pivot_offset(string, Pivot::BottomLeft, 12, wrap);
+        let offset = ver_pivot_offset(Pivot::BottomLeft, line_count(string, 12, wrap));
         assert_eq!(6, line_count(string, 12, false));
         assert_eq!(5, offset);
     }
@@ -744,4 +1406,243 @@ mod tests {
         assert_eq!('o', get_char(&map, [-1, 7]));
         assert_eq!('t', get_char(&map, [-1, 6]));
     }
+
+    #[test]
+    fn measure() {
+        let measure = measure_string("Use wasd to\nresize terminal", 8);
+        assert_eq!(UVec2::new(8, 4), measure.size());
+        let widths: Vec<_> = measure.lines.iter().map(|l| l.width).collect();
+        assert_eq!(vec![8, 2, 6, 8], widths);
+        assert_eq!(19..27, measure.lines[3].range);
+
+        let measure = TerminalString::from("Hello".delimiters("[]")).measure(10);
+        assert_eq!(UVec2::new(7, 1), measure.size());
+        assert_eq!(UVec2::ZERO, measure_string("  ", 10).size());
+    }
+
+    #[test]
+    fn align() {
+        let rect = GridRect::new([0, 0], [10, 5]);
+        let formatting = StringFormatting {
+            horizontal_alignment: Some(HorizontalAlignment::Right),
+            vertical_alignment: Some(VerticalAlignment::Bottom),
+            ..Default::default()
+        };
+        let map = make_map(StringIter::new(
+            "Hi\nabc",
+            rect,
+            [3, 3],
+            Some(formatting),
+            None,
+        ));
+        assert_eq!("        Hi", read_string(&map, [0, 1], 10));
+        assert_eq!("       abc", read_string(&map, [0, 0], 10));
+
+        let formatting = StringFormatting {
+            horizontal_alignment: Some(HorizontalAlignment::Center),
+            vertical_alignment: Some(VerticalAlignment::Center),
+            ..Default::default()
+        };
+        let map = make_map(StringIter::new("Hi", rect, [0, 0], Some(formatting), None));
+        assert_eq!("    Hi    ", read_string(&map, [0, 2], 10));
+    }
+
+    #[test]
+    fn justify() {
+        let rect = GridRect::new([0, 0], [12, 3]);
+        let formatting = StringFormatting {
+            horizontal_alignment: Some(HorizontalAlignment::Justify),
+            ..Default::default()
+        };
+        let map = make_map(StringIter::new(
+            "aa b cc dd ee",
+            rect,
+            [0, 0],
+            Some(formatting),
+            None,
+        ));
+        assert_eq!("aa  b  cc dd", read_string(&map, [0, 2], 12));
+        assert_eq!("ee          ", read_string(&map, [0, 1], 12));
+    }
+
+    #[test]
+    fn overflow() {
+        let rect = GridRect::new([0, 0], [10, 2]);
+        let formatting = StringFormatting {
+            overflow: StringOverflow::Ellipsis,
+            ..Default::default()
+        };
+        let string = "The quick brown fox jumps";
+        let map = make_map(StringIter::new(
+            string,
+            rect,
+            [0, 0],
+            Some(formatting),
+            None,
+        ));
+        assert_eq!("The quick ", read_string(&map, [0, 1], 10));
+        assert_eq!("brown f...", read_string(&map, [0, 0], 10));
+
+        let rect = GridRect::new([0, 0], [12, 1]);
+        let formatting = StringFormatting {
+            overflow: StringOverflow::TruncateMiddle,
+            ..Default::default()
+        };
+        let string = "assets/textures/player.png";
+        let map = make_map(StringIter::new(
+            string,
+            rect,
+            [0, 0],
+            Some(formatting),
+            None,
+        ));
+        assert_eq!("asset....png", read_string(&map, [0, 0], 12));
+    }
+
+    #[test]
+    fn truncate_middle_tabs() {
+        let rect = GridRect::new([0, 0], [10, 1]);
+        let formatting = StringFormatting {
+            overflow: StringOverflow::TruncateMiddle,
+            ..Default::default()
+        };
+        let string = "abcdefghijkl\nmn\t\tz";
+        let map = make_map(StringIter::new(
+            string,
+            rect,
+            [0, 0],
+            Some(formatting),
+            None,
+        ));
+        assert_eq!("abcdef...z", read_string(&map, [0, 0], 10));
+
+        let rect = GridRect::new([0, 0], [16, 1]);
+        let string = "abcdefghijklmnop\nmn\tz";
+        let map = make_map(StringIter::new(
+            string,
+            rect,
+            [0, 0],
+            Some(formatting),
+            None,
+        ));
+        assert_eq!("abcdef...mn z   ", read_string(&map, [0, 0], 16));
+    }
+
+    #[test]
+    fn max_lines() {
+        let rect = GridRect::new([0, 0], [10, 5]);
+        let formatting = StringFormatting {
+            max_lines: Some(2),
+            ..Default::default()
+        };
+        let map = make_map(StringIter::new(
+            "a\nb\nc",
+            rect,
+            [0, 0],
+            Some(formatting),
+            None,
+        ));
+        assert_eq!(2, map.len());
+        assert_eq!('b', get_char(&map, [0, 3]));
+
+        let measure = TerminalString::from("The quick brown fox jumps")
+            .max_lines(2)
+            .overflow(StringOverflow::Ellipsis)
+            .measure(10);
+        assert_eq!(UVec2::new(10, 2), measure.size());
+
+        let formatting = StringFormatting {
+            max_lines: Some(0),
+            ..Default::default()
+        };
+        let iter = StringIter::new("Hello", rect, [0, 0], Some(formatting), None);
+        assert_eq!(0, iter.count());
+        let term = crate::Terminal::new([10, 2]).with_string([0, 0], "Hello".max_lines(0));
+        assert!(term.tiles().iter().all(|t| *t == term.clear_tile()));
+        let measure = TerminalString::from("Hello").max_lines(0).measure(10);
+        assert_eq!(UVec2::ZERO, measure.size());
+    }
+
+    #[test]
+    fn wrap_multibyte() {
+        assert_eq!(Some(("░░░", "ééé")), wrap_string("░░░ ééé", 4, true));
+        assert_eq!(Some(("ééé", "éé")), wrap_string("ééééé", 3, false));
+        assert_eq!(Some(("é", "à")), wrap_string("é\nà", 5, true));
+        assert_eq!(2, line_count("░▒▓█ ░▒▓█", 5, true));
+    }
+
+    #[test]
+    fn pivot_multibyte() {
+        let rect = GridRect::new([0, 0], [10, 1]);
+        let p = [0, 0].pivot(Pivot::TopRight);
+        let map = make_map(StringIter::new("░▒▓", rect, p, None, None));
+        assert_eq!("       ░▒▓", read_string(&map, [0, 0], 10));
+
+        let formatting = StringFormatting {
+            horizontal_alignment: Some(HorizontalAlignment::Center),
+            ..Default::default()
+        };
+        let map = make_map(StringIter::new("éé", rect, [0, 0], Some(formatting), None));
+        assert_eq!("    éé    ", read_string(&map, [0, 0], 10));
+    }
+
+    #[test]
+    fn delimiters_multibyte() {
+        let rect = GridRect::new([0, 0], [10, 1]);
+        let decoration = StringDecoration {
+            delimiters: (Some('«'), Some('»')),
+            ..Default::default()
+        };
+        let map = make_map(StringIter::new("é░", rect, [0, 0], None, Some(decoration)));
+        assert_eq!("«é░»      ", read_string(&map, [0, 0], 10));
+
+        let measure = TerminalString::from("é░".delimiters("«»")).measure(10);
+        assert_eq!(UVec2::new(4, 1), measure.size());
+        assert_eq!(0..5, measure.lines[0].range);
+    }
+
+    #[test]
+    fn tabs() {
+        let rect = GridRect::new([0, 0], [12, 1]);
+        let map = make_map(StringIter::new("a\tbb\tc", rect, [0, 0], None, None));
+        assert_eq!("a   bb  c   ", read_string(&map, [0, 0], 12));
+
+        let formatting = StringFormatting {
+            tab_width: 2,
+            ..Default::default()
+        };
+        let map = make_map(StringIter::new(
+            "a\tbb\tc",
+            rect,
+            [0, 0],
+            Some(formatting),
+            None,
+        ));
+        assert_eq!("a bb  c     ", read_string(&map, [0, 0], 12));
+
+        let formatting = StringFormatting::default();
+        assert_eq!(Some(("a\tb", "c")), wrap_line("a\tb\tc", 6, &formatting));
+        assert_eq!(5, measure_string("a\tb\tc", 6).width);
+    }
+
+    #[test]
+    fn control_characters() {
+        let rect = GridRect::new([0, 0], [8, 1]);
+        let map = make_map(StringIter::new("hello\rJ\x01!", rect, [0, 0], None, None));
+        assert_eq!("J!llo   ", read_string(&map, [0, 0], 8));
+
+        let formatting = StringFormatting {
+            control_characters: ControlCharacters::Symbols,
+            ..Default::default()
+        };
+        let map = make_map(StringIter::new(
+            "\x01a\tb",
+            rect,
+            [0, 0],
+            Some(formatting),
+            None,
+        ));
+        let expected = format!("☺a{}b    ", ascii::index_to_char(9));
+        assert_eq!(expected, read_string(&map, [0, 0], 8));
+    }
 }
@@ -11,6 +11,7 @@ use sark_grids::{GridRect, GridSize, PivotedPoint};
 
 use crate::{
     ascii,
+    error::TerminalError,
     render::{
         RebuildMeshVerts, TerminalFont, TerminalMaterial, TerminalMeshPivot, UvMappingHandle,
     },
@@ -21,7 +22,18 @@ use crate::{
 };
 
 /// A grid of tiles for rendering colorful ascii.
+///
+/// With the `serde` feature enabled terminals can be serialized. Tile data is
+/// stored compactly as a color palette along with runs of identical tiles.
 #[derive(Debug, Reflect, Component, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "crate::compact::SerializedTerminal",
+        try_from = "crate::compact::SerializedTerminal"
+    )
+)]
 #[require(
     TerminalTransform,
     TerminalFont,
@@ -49,24 +61,50 @@ impl Terminal {
     /// Create a terminal from a REXPaint file. Note this writes all layers to the
     /// same terminal, so it won't preserve the transparent layering aspect of
     /// actual rexpaint files.
-    pub fn from_rexpaint_file(file_path: impl AsRef<str>) -> std::io::Result<Self> {
+    ///
+    /// This reads the file directly from disk. To load a REXPaint file through
+    /// the asset server see [crate::rexpaint::XpAssetHandle].
+    pub fn from_rexpaint_file(file_path: impl AsRef<str>) -> Result<Self, TerminalError> {
         let mut file = std::fs::File::open(file_path.as_ref())?;
         let xp = XpFile::read(&mut file)?;
-        let Some((w, h)) = xp.layers.first().map(|l| (l.width, l.height)) else {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "No layers found in REXPaint file",
+        Self::from_rexpaint(&xp)
+    }
+
+    /// Create a terminal from REXPaint image data. Note this writes all layers to the
+    /// same terminal, so it won't preserve the transparent layering aspect of
+    /// actual rexpaint files.
+    ///
+    /// All layers must be the same size and every cell must contain a valid
+    /// code page 437 glyph index.
+    pub fn from_rexpaint(xp: &XpFile) -> Result<Self, TerminalError> {
+        let Some(size) = xp
+            .layers
+            .first()
+            .map(|l| UVec2::new(l.width as u32, l.height as u32))
+        else {
+            return Err(TerminalError::InvalidFormat(
+                "No layers found in REXPaint file".to_string(),
             ));
         };
-        let mut terminal = Self::new([w, h]);
+        if size.cmpeq(UVec2::ZERO).any() {
+            return Err(TerminalError::InvalidDimensions(size));
+        }
+        let mut terminal = Self::new(size);
         for layer in &xp.layers {
+            let layer_size = UVec2::new(layer.width as u32, layer.height as u32);
+            if layer_size != size {
+                return Err(TerminalError::MismatchedLayerSizes {
+                    expected: size,
+                    found: layer_size,
+                });
+            }
             for y in 0..layer.height {
                 for x in 0..layer.width {
                     let cell = layer.get(x, y).unwrap();
-                    let Some(glyph) = char::from_u32(cell.ch) else {
-                        continue;
-                    };
-                    let glyph = ascii::try_index_to_char(glyph as u8).unwrap_or(' ');
+                    let glyph = u8::try_from(cell.ch)
+                        .ok()
+                        .and_then(ascii::try_index_to_char)
+                        .ok_or(TerminalError::UnsupportedGlyph(cell.ch))?;
                     let frgb = [cell.fg.r, cell.fg.g, cell.fg.b, 255];
                     let brgb = [cell.bg.r, cell.bg.g, cell.bg.b, 255];
                     let fg = LinearRgba::from_u8_array(frgb);
@@ -85,25 +123,42 @@ impl Terminal {
     /// Empty lines will be ignored, add a space if you want an actual empty row
     /// built into the terminal.
     ///
+    /// Returns [TerminalError::InvalidDimensions] if the string has no
+    /// non-empty lines.
+    ///
     /// # Example
     /// ```
     /// use bevy_ascii_terminal::Terminal;
     /// let terminal = Terminal::from_string("Hello\nWorld").unwrap();
     /// ```
-    pub fn from_string(string: impl AsRef<str>) -> Option<Self> {
-        let width = string.as_ref().lines().map(|l| l.len()).max()?;
+    pub fn from_string(string: impl AsRef<str>) -> Result<Self, TerminalError> {
+        let width = string
+            .as_ref()
+            .lines()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or_default();
         let height = string.as_ref().lines().filter(|l| !l.is_empty()).count();
         if width == 0 || height == 0 {
-            return None;
+            return Err(TerminalError::InvalidDimensions(UVec2::new(
+                width as u32,
+                height as u32,
+            )));
         }
         let mut terminal = Self::new([width, height]);
-        for (y, line) in string.as_ref().lines().rev().enumerate() {
+        for (y, line) in string
+            .as_ref()
+            .lines()
+            .filter(|l| !l.is_empty())
+            .rev()
+            .enumerate()
+        {
             for (x, ch) in line.chars().enumerate() {
                 let t = terminal.tile_mut([x as i32, y as i32]);
                 t.glyph = ch;
             }
         }
-        Some(terminal)
+        Ok(terminal)
     }
 
     /// Specify the terminal's `clear tile`. This is the default tile used when
@@ -202,12 +257,17 @@ impl Terminal {
     /// By default strings will be written to the top left of the terminal. You
     /// can apply a pivot to the xy position to change this.
     ///
+    /// Strings can also be aligned within the terminal independently of the
+    /// position with [crate::string::HorizontalAlignment] and
+    /// [crate::string::VerticalAlignment], see [crate::string::StringFormatter].
+    ///
     /// # Example
     /// ```
-    /// use bevy_ascii_terminal::*;
+    /// use bevy_ascii_terminal::{string::*, *};
     /// let mut terminal = Terminal::new([10, 10]);
     /// terminal.put_string([5, 5], "Hello, World!".bg(color::BLUE));
     /// terminal.put_string([1, 1].pivot(Pivot::BottomLeft), "Beep beep!");
+    /// terminal.put_string([0, 0], "Centered".align(HorizontalAlignment::Center));
     /// ```
     pub fn put_string<T: AsRef<str>>(
         &mut self,
@@ -407,3 +467,61 @@ impl Terminal {
         self.size = new_size;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::rexpaint::reader::{XpCell, XpColor, XpLayer};
+
+    use super::*;
+
+    fn xp_layer(width: usize, height: usize, ch: u32) -> XpLayer {
+        let cell = XpCell {
+            ch,
+            fg: XpColor::BLACK,
+            bg: XpColor::BLACK,
+        };
+        XpLayer {
+            width,
+            height,
+            cells: vec![cell; width * height],
+        }
+    }
+
+    #[test]
+    fn from_string() {
+        let term = Terminal::from_string("Hello\n\nWorld!").unwrap();
+        assert_eq!([6, 2], term.size().to_array());
+        assert_eq!('H', term.tile([0, 1]).glyph);
+        assert_eq!('!', term.tile([5, 0]).glyph);
+        assert!(matches!(
+            Terminal::from_string("\n\n"),
+            Err(TerminalError::InvalidDimensions(_))
+        ));
+    }
+
+    #[test]
+    fn from_rexpaint_errors() {
+        let xp = XpFile {
+            version: 0,
+            layers: vec![xp_layer(3, 3, 1), xp_layer(4, 3, 1)],
+        };
+        assert!(matches!(
+            Terminal::from_rexpaint(&xp),
+            Err(TerminalError::MismatchedLayerSizes { .. })
+        ));
+        let xp = XpFile {
+            version: 0,
+            layers: vec![xp_layer(3, 3, 300)],
+        };
+        assert!(matches!(
+            Terminal::from_rexpaint(&xp),
+            Err(TerminalError::UnsupportedGlyph(300))
+        ));
+        let xp = XpFile {
+            version: 0,
+            layers: vec![xp_layer(3, 3, 1)],
+        };
+        let term = Terminal::from_rexpaint(&xp).unwrap();
+        assert_eq!('☺', term.tile([1, 1]).glyph);
+    }
+}
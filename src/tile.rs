@@ -6,6 +6,7 @@ use bevy::{
 };
 
 #[derive(Debug, Clone, Reflect, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tile {
     pub glyph: char,
     pub fg_color: LinearRgba,
@@ -112,18 +112,37 @@ impl TerminalTransform {
     /// For accurate results this should be called after
     /// [TerminalSystemsUpdateTransform] which runs in [PostUpdate].
     pub fn world_to_tile(&self, world_pos: Vec2) -> Option<IVec2> {
-        let Some(data) = &self.cached_data else {
-            return None;
-        };
-        let min = data.world_pos.truncate() + data.local_inner_mesh_bounds.min;
-        let pos = ((world_pos - min) / data.world_tile_size)
-            .floor()
-            .as_ivec2();
+        let data = self.cached_data.as_ref()?;
+        let pos = self.world_to_tile_unbounded(world_pos)?;
         if pos.cmplt(IVec2::ZERO).any() || pos.cmpge(data.terminal_size.as_ivec2()).any() {
             return None;
         }
         Some(pos)
     }
+
+    /// Convert a world position into a local 2d tile index, without checking
+    /// if the position is within the bounds of the terminal.
+    ///
+    /// Will return [None] if the transform data has not been cached yet.
+    pub fn world_to_tile_unbounded(&self, world_pos: Vec2) -> Option<IVec2> {
+        let data = self.cached_data.as_ref()?;
+        let min = data.world_pos.truncate() + data.local_inner_mesh_bounds.min;
+        Some(
+            ((world_pos - min) / data.world_tile_size)
+                .floor()
+                .as_ivec2(),
+        )
+    }
+
+    /// Convert a local 2d tile index into the world position of the center of
+    /// that tile. The tile doesn't need to be within the bounds of the terminal.
+    ///
+    /// Will return [None] if the transform data has not been cached yet.
+    pub fn tile_to_world(&self, tile: impl GridPoint) -> Option<Vec2> {
+        let data = self.cached_data.as_ref()?;
+        let min = data.world_pos.truncate() + data.local_inner_mesh_bounds.min;
+        Some(min + (tile.to_ivec2().as_vec2() + 0.5) * data.world_tile_size)
+    }
 }
 
 fn on_image_load(
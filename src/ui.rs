@@ -0,0 +1,345 @@
+//! Rendering terminals inside `bevy_ui` layouts.
+//!
+//! A [TerminalUiNode] renders a terminal into an image with it's own
+//! [TerminalCamera], and displays that image in an [ImageNode]. The node is
+//! sized from the terminal's tile dimensions so it can be placed in any UI
+//! layout like a regular image.
+
+use bevy::{
+    app::{Plugin, PostUpdate, PreUpdate},
+    asset::{Assets, RenderAssetUsages},
+    ecs::{
+        change_detection::DetectChangesMut,
+        component::Component,
+        entity::Entity,
+        event::EventWriter,
+        observer::Trigger,
+        schedule::IntoSystemConfigs,
+        system::{Commands, Query, ResMut, Resource},
+        world::OnRemove,
+    },
+    image::{Image, ImageSampler},
+    math::{IVec2, UVec2, Vec2},
+    render::{
+        camera::{Camera, ClearColorConfig, RenderTarget},
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+    ui::{widget::ImageNode, RelativeCursorPosition, UiSystem},
+};
+
+use crate::{
+    mouse::TerminalSystemsMouseInput,
+    render::{TerminalSystemsUpdateCamera, UpdateTerminalViewportEvent},
+    transform::{TerminalSystemsUpdateTransform, TerminalTransform},
+    TerminalCamera, TerminalMouse,
+};
+
+pub(crate) struct TerminalUiPlugin;
+
+impl Plugin for TerminalUiPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<TerminalUiLayers>()
+            .add_observer(on_node_removed)
+            .add_systems(
+                PreUpdate,
+                update_cursor
+                    .after(UiSystem::Focus)
+                    .before(TerminalSystemsMouseInput),
+            )
+            .add_systems(
+                PostUpdate,
+                update_nodes
+                    .after(TerminalSystemsUpdateTransform)
+                    .before(TerminalSystemsUpdateCamera),
+            );
+    }
+}
+
+/// The first render layer used for terminal ui nodes. Each node is assigned
+/// it's own render layer starting from this one.
+pub const TERMINAL_UI_FIRST_LAYER: usize = 16;
+
+/// Tracks the render layers used by terminal ui nodes. Layers are released
+/// when their node is removed so they can be reused.
+#[derive(Resource)]
+struct TerminalUiLayers {
+    next: usize,
+    free: Vec<usize>,
+}
+
+impl Default for TerminalUiLayers {
+    fn default() -> Self {
+        Self {
+            next: TERMINAL_UI_FIRST_LAYER,
+            free: Vec::new(),
+        }
+    }
+}
+
+impl TerminalUiLayers {
+    fn take(&mut self) -> usize {
+        self.free.pop().unwrap_or_else(|| {
+            self.next += 1;
+            self.next - 1
+        })
+    }
+
+    fn release(&mut self, layer: usize) {
+        self.free.push(layer);
+    }
+}
+
+/// A ui node which displays a terminal.
+///
+/// The terminal is rendered to an image by a dedicated [TerminalCamera], and
+/// that image is displayed in the node's [ImageNode]. The terminal entity is
+/// moved to it's own [RenderLayers] so it won't be rendered by any other
+/// camera. When the node is removed the layer is released and the terminal's
+/// original [RenderLayers], if it had any, are restored.
+///
+/// [crate::TerminalMouseEvent]s are sent for the terminal based on the cursor
+/// position over the node, so widgets work inside ui layouts the same as they
+/// do in the world.
+///
+/// # Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_ascii_terminal::{ui::TerminalUiNode, *};
+///
+/// fn setup(mut commands: Commands) {
+///     let term = commands.spawn(Terminal::new([20, 10])).id();
+///     commands.spawn(Camera2d);
+///     commands
+///         .spawn(Node {
+///             padding: UiRect::all(Val::Px(10.0)),
+///             ..default()
+///         })
+///         .with_child(TerminalUiNode::new(term).with_zoom(2));
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+#[require(ImageNode, RelativeCursorPosition)]
+pub struct TerminalUiNode {
+    /// The terminal entity to display.
+    pub terminal: Entity,
+    /// The number of ui pixels per font pixel. Values less than 1 are treated
+    /// as 1.
+    pub zoom: u32,
+    camera: Option<Entity>,
+    layer: Option<usize>,
+    /// The terminal's render layers from before it was moved to `layer`.
+    original_layers: Option<RenderLayers>,
+    image_size: UVec2,
+    cursor_tile: Option<IVec2>,
+}
+
+impl TerminalUiNode {
+    pub fn new(terminal: Entity) -> Self {
+        Self {
+            terminal,
+            zoom: 1,
+            camera: None,
+            layer: None,
+            original_layers: None,
+            image_size: UVec2::ZERO,
+            cursor_tile: None,
+        }
+    }
+
+    pub fn with_zoom(mut self, zoom: u32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    /// The terminal tile under the cursor, if the cursor is over the node.
+    ///
+    /// Border tiles are included, so the tile may be outside the bounds of
+    /// the terminal.
+    pub fn cursor_tile(&self) -> Option<IVec2> {
+        self.cursor_tile
+    }
+
+    /// The camera entity rendering the terminal for this node, if it's been
+    /// created.
+    pub fn camera(&self) -> Option<Entity> {
+        self.camera
+    }
+}
+
+fn new_target_image(size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            ..Default::default()
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+fn update_nodes(
+    mut q_node: Query<(&mut TerminalUiNode, &mut ImageNode)>,
+    q_term: Query<(&TerminalTransform, Option<&RenderLayers>)>,
+    mut images: ResMut<Assets<Image>>,
+    mut layers: ResMut<TerminalUiLayers>,
+    mut commands: Commands,
+    mut vp_evt: EventWriter<UpdateTerminalViewportEvent>,
+) {
+    for (mut node, mut image_node) in &mut q_node {
+        let Ok((transform, term_layers)) = q_term.get(node.terminal) else {
+            continue;
+        };
+        let Some(data) = transform.cached_data.as_ref() else {
+            continue;
+        };
+        // Font images may still be loading
+        if data.pixels_per_tile.cmpeq(UVec2::ZERO).any() {
+            continue;
+        }
+        let tile_count = (data.world_mesh_bounds.size() / data.world_tile_size)
+            .round()
+            .as_uvec2();
+        let size = tile_count * data.pixels_per_tile * node.zoom.max(1);
+        if size == node.image_size {
+            continue;
+        }
+        node.image_size = size;
+
+        if node.camera.is_none() {
+            let handle = images.add(new_target_image(size));
+            let index = layers.take();
+            node.layer = Some(index);
+            node.original_layers = term_layers.cloned();
+            let layer = RenderLayers::layer(index);
+            commands.entity(node.terminal).insert(layer.clone());
+            // The cursor is mapped through the node rather than the camera
+            let mut terminal_cam = TerminalCamera::new().with_frame_target(node.terminal);
+            terminal_cam.track_cursor = false;
+            let camera = commands
+                .spawn((
+                    terminal_cam,
+                    Camera {
+                        target: RenderTarget::Image(handle.clone()),
+                        clear_color: ClearColorConfig::Custom(bevy::color::Color::NONE),
+                        order: -1,
+                        ..Default::default()
+                    },
+                    layer,
+                ))
+                .id();
+            node.camera = Some(camera);
+            image_node.image = handle;
+        } else if let Some(image) = images.get_mut(&image_node.image) {
+            image.resize(Extent3d {
+                width: size.x,
+                height: size.y,
+                ..Default::default()
+            });
+            // Force the image node to update it's size
+            image_node.set_changed();
+            vp_evt.send(UpdateTerminalViewportEvent);
+        }
+    }
+}
+
+fn update_cursor(
+    mut q_node: Query<(&mut TerminalUiNode, &RelativeCursorPosition)>,
+    q_term: Query<&TerminalTransform>,
+    mut mouse: ResMut<TerminalMouse>,
+) {
+    mouse.ui_cursors.clear();
+    for (mut node, cursor) in &mut q_node {
+        let transform = q_term.get(node.terminal).ok();
+        let world = cursor
+            .normalized
+            .filter(|_| cursor.mouse_over())
+            .and_then(|pos| {
+                let bounds = transform?.cached_data.as_ref()?.world_mesh_bounds;
+                // Ui positions have the y axis pointing down
+                Some(bounds.min + Vec2::new(pos.x, 1.0 - pos.y) * bounds.size())
+            });
+        // Mouse events for the terminal are sent from the node's cursor
+        mouse.ui_cursors.insert(node.terminal, world);
+        let tile = world.and_then(|world| transform?.world_to_tile_unbounded(world));
+        if node.cursor_tile != tile {
+            node.cursor_tile = tile;
+        }
+    }
+}
+
+fn on_node_removed(
+    trigger: Trigger<OnRemove, TerminalUiNode>,
+    q_node: Query<&TerminalUiNode>,
+    mut layers: ResMut<TerminalUiLayers>,
+    mut commands: Commands,
+) {
+    let Ok(node) = q_node.get(trigger.entity()) else {
+        return;
+    };
+    if let Some(camera) = node.camera {
+        if let Some(mut camera) = commands.get_entity(camera) {
+            camera.despawn();
+        }
+    }
+    if let Some(layer) = node.layer {
+        // The terminal would be rendered by the next node to use the layer
+        if let Some(mut term) = commands.get_entity(node.terminal) {
+            match node.original_layers.clone() {
+                Some(original) => term.insert(original),
+                None => term.remove::<RenderLayers>(),
+            };
+        }
+        layers.release(layer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::world::World;
+
+    use super::*;
+
+    #[test]
+    fn reuse_layers() {
+        let mut layers = TerminalUiLayers::default();
+        let a = layers.take();
+        let b = layers.take();
+        assert_eq!(
+            [TERMINAL_UI_FIRST_LAYER, TERMINAL_UI_FIRST_LAYER + 1],
+            [a, b]
+        );
+        layers.release(a);
+        assert_eq!(a, layers.take());
+        assert_eq!(TERMINAL_UI_FIRST_LAYER + 2, layers.take());
+    }
+
+    #[test]
+    fn restore_layers() {
+        let mut world = World::new();
+        world.init_resource::<TerminalUiLayers>();
+        world.add_observer(on_node_removed);
+        let index = world.resource_mut::<TerminalUiLayers>().take();
+        let term = world.spawn(RenderLayers::layer(index)).id();
+        let mut node = TerminalUiNode::new(term);
+        node.layer = Some(index);
+        node.original_layers = Some(RenderLayers::layer(3));
+        let node = world.spawn(node).id();
+
+        world.despawn(node);
+        world.flush();
+        assert_eq!(
+            Some(&RenderLayers::layer(3)),
+            world.get::<RenderLayers>(term)
+        );
+        assert_eq!(index, world.resource_mut::<TerminalUiLayers>().take());
+    }
+}
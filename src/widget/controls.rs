@@ -0,0 +1,504 @@
+//! Immediate mode widgets.
+//!
+//! Each widget is a function which draws into a [GridRect] of a [Terminal]
+//! and reacts to the [WidgetInput] for that terminal. Widgets should be drawn
+//! every frame the terminal is redrawn, any state which needs to persist
+//! between frames is passed in by the caller.
+//!
+//! Rect positions use the same coordinates as [Terminal::tile_mut], with the
+//! origin at the bottom left of the terminal. Multi row widgets are laid out
+//! from the top row of their rect downwards.
+
+use std::ops::RangeInclusive;
+
+use bevy::{color::LinearRgba, input::keyboard::KeyCode, math::IVec2};
+use sark_grids::GridRect;
+
+use crate::{color, Terminal};
+
+use super::{clip_rect, WidgetInput};
+
+/// Colors used when drawing widgets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WidgetStyle {
+    pub fg: LinearRgba,
+    pub bg: LinearRgba,
+    /// The background color of a widget under the cursor.
+    pub hover_bg: LinearRgba,
+    /// The background color of a widget while the mouse button is held down
+    /// over it.
+    pub pressed_bg: LinearRgba,
+    /// The background color of selected list items.
+    pub selected_bg: LinearRgba,
+    /// The color used for checkmarks, slider handles and progress bars.
+    pub accent: LinearRgba,
+}
+
+impl Default for WidgetStyle {
+    fn default() -> Self {
+        Self {
+            fg: color::WHITE,
+            bg: color::DARK_SLATE_GRAY,
+            hover_bg: color::DIM_GRAY,
+            pressed_bg: color::NAVY,
+            selected_bg: color::ROYAL_BLUE,
+            accent: color::GOLD,
+        }
+    }
+}
+
+impl WidgetStyle {
+    /// The background color for a widget in the given rect.
+    fn state_bg(&self, rect: GridRect, input: &WidgetInput) -> LinearRgba {
+        if input.is_pressed(rect) {
+            self.pressed_bg
+        } else if input.is_hovered(rect) {
+            self.hover_bg
+        } else {
+            self.bg
+        }
+    }
+}
+
+/// Fill the part of a rect inside the terminal with blank tiles.
+fn fill_rect(term: &mut Terminal, rect: GridRect, fg: LinearRgba, bg: LinearRgba) {
+    for p in clip_rect(rect, term.bounds()).iter_points() {
+        term.put_char(p, ' ').fg(fg).bg(bg);
+    }
+}
+
+/// Write a single line of text, truncated to `max_width` and clipped to the
+/// terminal bounds. Background colors are left untouched.
+fn put_text(term: &mut Terminal, xy: IVec2, text: &str, max_width: usize, fg: LinearRgba) {
+    let bounds = term.bounds();
+    for (i, ch) in text.chars().take(max_width).enumerate() {
+        let p = xy + IVec2::new(i as i32, 0);
+        if bounds.contains_point(p) {
+            term.put_char(p, ch).fg(fg);
+        }
+    }
+}
+
+/// The y position of row `i` of a rect, counting down from the top.
+fn row_y(rect: GridRect, i: usize) -> i32 {
+    rect.top() - i as i32
+}
+
+/// A clickable button with a centered label.
+///
+/// Returns true if the button was clicked this frame.
+pub fn button(
+    term: &mut Terminal,
+    rect: GridRect,
+    label: &str,
+    input: &WidgetInput,
+    style: &WidgetStyle,
+) -> bool {
+    fill_rect(term, rect, style.fg, style.state_bg(rect, input));
+    let len = label.chars().count().min(rect.width());
+    let x = rect.left() + (rect.width() - len) as i32 / 2;
+    let y = row_y(rect, (rect.height().max(1) - 1) / 2);
+    put_text(term, IVec2::new(x, y), label, len, style.fg);
+    input.clicked(rect).is_some()
+}
+
+/// A checkbox drawn as `[x] label` on the top row of the rect. Clicking
+/// anywhere on the row toggles the value.
+///
+/// Returns true if the value was changed this frame.
+pub fn checkbox(
+    term: &mut Terminal,
+    rect: GridRect,
+    label: &str,
+    checked: &mut bool,
+    input: &WidgetInput,
+    style: &WidgetStyle,
+) -> bool {
+    let row = GridRect::new([rect.left(), rect.top()], [rect.width(), 1]);
+    let changed = input.clicked(row).is_some();
+    if changed {
+        *checked = !*checked;
+    }
+    fill_rect(term, row, style.fg, style.state_bg(row, input));
+    let xy = row.bottom_left();
+    let width = row.width();
+    put_text(term, xy, "[ ]", width, style.fg);
+    if *checked {
+        put_text(
+            term,
+            xy + IVec2::X,
+            "x",
+            width.saturating_sub(1),
+            style.accent,
+        );
+    }
+    put_text(
+        term,
+        xy + IVec2::X * 4,
+        label,
+        width.saturating_sub(4),
+        style.fg,
+    );
+    changed
+}
+
+/// A group of mutually exclusive options, one per row from the top of the
+/// rect. Options that don't fit in the rect are not drawn.
+///
+/// Returns true if the selected option was changed this frame.
+pub fn radio_group(
+    term: &mut Terminal,
+    rect: GridRect,
+    options: &[&str],
+    selected: &mut usize,
+    input: &WidgetInput,
+    style: &WidgetStyle,
+) -> bool {
+    let mut changed = false;
+    let width = rect.width();
+    for i in 0..options.len().min(rect.height()) {
+        let row = GridRect::new([rect.left(), row_y(rect, i)], [width, 1]);
+        if input.clicked(row).is_some() && *selected != i {
+            *selected = i;
+            changed = true;
+        }
+    }
+    for (i, option) in options.iter().enumerate().take(rect.height()) {
+        let row = GridRect::new([rect.left(), row_y(rect, i)], [width, 1]);
+        let xy = row.bottom_left();
+        fill_rect(term, row, style.fg, style.state_bg(row, input));
+        put_text(term, xy, "( )", width, style.fg);
+        if *selected == i {
+            put_text(
+                term,
+                xy + IVec2::X,
+                "•",
+                width.saturating_sub(1),
+                style.accent,
+            );
+        }
+        put_text(
+            term,
+            xy + IVec2::X * 4,
+            option,
+            width.saturating_sub(4),
+            style.fg,
+        );
+    }
+    changed
+}
+
+/// The persistent state of a [list] widget.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListState {
+    /// The index of the selected item.
+    pub selected: Option<usize>,
+    /// The index of the first visible item.
+    pub scroll: usize,
+    /// Whether the list receives keyboard input. Clicking the list focuses
+    /// it, clicking elsewhere in the terminal removes focus.
+    pub focused: bool,
+}
+
+/// The result of drawing a [list] widget.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ListResponse {
+    /// The selected item changed this frame.
+    pub changed: bool,
+    /// An item was activated this frame, by double clicking it or pressing
+    /// enter while it was selected.
+    pub activated: Option<usize>,
+}
+
+impl ListState {
+    /// Move the selection in response to a key press, for a list with `len`
+    /// items where `page` items are visible at once.
+    ///
+    /// Returns true if the key was handled.
+    pub fn navigate(&mut self, key: KeyCode, len: usize, page: usize) -> bool {
+        if len == 0 {
+            return false;
+        }
+        let last = len - 1;
+        let page = page.max(1);
+        let next = match (key, self.selected) {
+            (KeyCode::ArrowUp, Some(i)) => i.saturating_sub(1),
+            (KeyCode::ArrowDown, Some(i)) => (i + 1).min(last),
+            (KeyCode::PageUp, Some(i)) => i.saturating_sub(page),
+            (KeyCode::PageDown, Some(i)) => (i + page).min(last),
+            (KeyCode::ArrowUp | KeyCode::ArrowDown | KeyCode::PageUp | KeyCode::PageDown, None) => {
+                0
+            }
+            (KeyCode::Home, _) => 0,
+            (KeyCode::End, _) => last,
+            _ => return false,
+        };
+        self.selected = Some(next);
+        true
+    }
+
+    /// Adjust the scroll position so the selected item is visible.
+    pub fn scroll_to_selected(&mut self, page: usize) {
+        if let Some(i) = self.selected {
+            if i < self.scroll {
+                self.scroll = i;
+            } else if i >= self.scroll + page.max(1) {
+                self.scroll = i + 1 - page.max(1);
+            }
+        }
+    }
+}
+
+/// A scrollable list of selectable items, one per row.
+///
+/// Clicking an item selects it and focuses the list, the mouse wheel scrolls
+/// the list. While focused the arrow keys, page up, page down, home and end
+/// move the selection.
+pub fn list<T: AsRef<str>>(
+    term: &mut Terminal,
+    rect: GridRect,
+    items: &[T],
+    state: &mut ListState,
+    input: &WidgetInput,
+    style: &WidgetStyle,
+) -> ListResponse {
+    let mut response = ListResponse::default();
+    let page = rect.height();
+    if page == 0 {
+        return response;
+    }
+    let previous = state.selected;
+    if let Some(p) = input.clicked(rect) {
+        state.focused = true;
+        let i = state.scroll + (rect.top() - p.y) as usize;
+        if i < items.len() {
+            state.selected = Some(i);
+        }
+    } else if !input.clicks.is_empty() {
+        state.focused = false;
+    }
+    if let Some(p) = input.double_clicked(rect) {
+        let i = state.scroll + (rect.top() - p.y) as usize;
+        if i < items.len() {
+            response.activated = Some(i);
+        }
+    }
+
+    if state.focused {
+        for key in &input.keys {
+            if *key == KeyCode::Enter {
+                response.activated = response.activated.or(state.selected);
+            } else if state.navigate(*key, items.len(), page) {
+                state.scroll_to_selected(page);
+            }
+        }
+    }
+
+    let max_scroll = items.len().saturating_sub(page);
+    let wheel = input.wheel(rect);
+    if wheel > 0.0 {
+        state.scroll = state.scroll.saturating_sub(wheel.ceil() as usize);
+    } else if wheel < 0.0 {
+        state.scroll += (-wheel).ceil() as usize;
+    }
+    state.selected = state.selected.filter(|i| *i < items.len());
+    state.scroll = state.scroll.min(max_scroll);
+    response.changed = state.selected != previous;
+
+    let width = rect.width();
+    for row in 0..page {
+        let y = row_y(rect, row);
+        let row_rect = GridRect::new([rect.left(), y], [width, 1]);
+        let i = state.scroll + row;
+        let bg = if state.selected == Some(i) {
+            style.selected_bg
+        } else if input.is_hovered(row_rect) && i < items.len() {
+            style.hover_bg
+        } else {
+            style.bg
+        };
+        fill_rect(term, row_rect, style.fg, bg);
+        if let Some(item) = items.get(i) {
+            put_text(term, row_rect.bottom_left(), item.as_ref(), width, style.fg);
+        }
+    }
+    response
+}
+
+/// A horizontal slider on the top row of the rect. The value can be set by
+/// clicking or dragging along the slider.
+///
+/// Returns true if the value was changed this frame.
+pub fn slider(
+    term: &mut Terminal,
+    rect: GridRect,
+    value: &mut f32,
+    range: RangeInclusive<f32>,
+    input: &WidgetInput,
+    style: &WidgetStyle,
+) -> bool {
+    let row = GridRect::new([rect.left(), rect.top()], [rect.width(), 1]);
+    let (min, max) = (*range.start(), *range.end());
+    let steps = row.width().saturating_sub(1) as i32;
+    let mut changed = false;
+    let target = if input.is_pressed(row) {
+        input.hovered
+    } else {
+        input.clicked(row)
+    };
+    if let Some(p) = target {
+        let t = if steps == 0 {
+            0.0
+        } else {
+            (p.x - row.left()).clamp(0, steps) as f32 / steps as f32
+        };
+        let v = min + (max - min) * t;
+        if v != *value {
+            *value = v;
+            changed = true;
+        }
+    }
+
+    let t = if max > min {
+        ((*value - min) / (max - min)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let handle = (t * steps as f32).round() as i32;
+    let bg = style.state_bg(row, input);
+    let bounds = term.bounds();
+    for x in 0..row.width() as i32 {
+        let p = row.bottom_left() + IVec2::new(x, 0);
+        if !bounds.contains_point(p) {
+            continue;
+        }
+        if x == handle {
+            term.put_char(p, '█').fg(style.accent).bg(bg);
+        } else {
+            term.put_char(p, '─').fg(style.fg).bg(bg);
+        }
+    }
+    changed
+}
+
+/// A horizontal progress bar on the top row of the rect, filled from the left
+/// with half tile precision. `fraction` is clamped to the range 0..=1.
+pub fn progress_bar(term: &mut Terminal, rect: GridRect, fraction: f32, style: &WidgetStyle) {
+    let row = GridRect::new([rect.left(), rect.top()], [rect.width(), 1]);
+    let halves = (fraction.clamp(0.0, 1.0) * row.width() as f32 * 2.0).round() as usize;
+    let bounds = term.bounds();
+    for x in 0..row.width() {
+        let p = row.bottom_left() + IVec2::new(x as i32, 0);
+        if !bounds.contains_point(p) {
+            continue;
+        }
+        let glyph = match halves.saturating_sub(x * 2) {
+            0 => '░',
+            1 => '▌',
+            _ => '█',
+        };
+        term.put_char(p, glyph).fg(style.accent).bg(style.bg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_row(term: &Terminal, y: i32, width: usize) -> String {
+        (0..width as i32).map(|x| term.tile([x, y]).glyph).collect()
+    }
+
+    #[test]
+    fn button_click() {
+        let mut term = Terminal::new([10, 3]);
+        let rect = GridRect::new([0, 0], [10, 3]);
+        let style = WidgetStyle::default();
+        assert!(!button(
+            &mut term,
+            rect,
+            "Ok",
+            &WidgetInput::default(),
+            &style
+        ));
+        assert_eq!("    Ok    ", read_row(&term, 1, 10));
+
+        let input = WidgetInput::default().click([9, 2]);
+        assert!(button(&mut term, rect, "Ok", &input, &style));
+        assert_eq!(style.hover_bg, term.tile([0, 0]).bg_color);
+
+        // A button outside the terminal draws nothing
+        let mut term = Terminal::new([10, 3]);
+        let outside = GridRect::new([12, 0], [4, 3]);
+        button(&mut term, outside, "Ok", &WidgetInput::default(), &style);
+        assert!(term.tiles().iter().all(|t| *t == term.clear_tile()));
+    }
+
+    #[test]
+    fn checkbox_toggle() {
+        let mut term = Terminal::new([12, 2]);
+        let rect = GridRect::new([0, 0], [12, 2]);
+        let style = WidgetStyle::default();
+        let mut checked = false;
+        let input = WidgetInput::default().click([6, 1]);
+        assert!(checkbox(
+            &mut term,
+            rect,
+            "Sound",
+            &mut checked,
+            &input,
+            &style
+        ));
+        assert!(checked);
+        assert_eq!("[x] Sound   ", read_row(&term, 1, 12));
+    }
+
+    #[test]
+    fn list_navigation() {
+        let mut state = ListState::default();
+        assert!(state.navigate(KeyCode::ArrowDown, 10, 3));
+        assert_eq!(Some(0), state.selected);
+        state.navigate(KeyCode::PageDown, 10, 3);
+        state.navigate(KeyCode::PageDown, 10, 3);
+        state.scroll_to_selected(3);
+        assert_eq!(Some(6), state.selected);
+        assert_eq!(4, state.scroll);
+        state.navigate(KeyCode::End, 10, 3);
+        state.navigate(KeyCode::ArrowDown, 10, 3);
+        assert_eq!(Some(9), state.selected);
+        state.navigate(KeyCode::Home, 10, 3);
+        state.scroll_to_selected(3);
+        assert_eq!(0, state.scroll);
+        assert!(!state.navigate(KeyCode::KeyA, 10, 3));
+    }
+
+    #[test]
+    fn list_click() {
+        let mut term = Terminal::new([8, 4]);
+        let rect = GridRect::new([0, 0], [8, 3]);
+        let items = ["a", "b", "c", "d"];
+        let style = WidgetStyle::default();
+        let mut state = ListState {
+            scroll: 1,
+            ..Default::default()
+        };
+        // Row 1 from the top shows the item at index scroll + 1
+        let input = WidgetInput::default().click([3, 1]);
+        let response = list(&mut term, rect, &items, &mut state, &input, &style);
+        assert!(response.changed);
+        assert!(state.focused);
+        assert_eq!(Some(2), state.selected);
+        assert_eq!(style.selected_bg, term.tile([0, 1]).bg_color);
+        assert_eq!('c', term.tile([0, 1]).glyph);
+    }
+
+    #[test]
+    fn progress_glyphs() {
+        let mut term = Terminal::new([4, 1]);
+        let rect = GridRect::new([0, 0], [4, 1]);
+        progress_bar(&mut term, rect, 0.375, &WidgetStyle::default());
+        assert_eq!("█▌░░", read_row(&term, 0, 4));
+        progress_bar(&mut term, rect, 2.0, &WidgetStyle::default());
+        assert_eq!("████", read_row(&term, 0, 4));
+    }
+}
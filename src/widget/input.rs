@@ -0,0 +1,130 @@
+use bevy::{
+    ecs::entity::Entity,
+    input::{keyboard::KeyCode, mouse::MouseButton, ButtonInput},
+    math::IVec2,
+};
+use sark_grids::{GridPoint, GridRect};
+
+use crate::{TerminalMouse, TerminalMouseEvent, TerminalMouseEventKind};
+
+/// A snapshot of the input for a single terminal, used by immediate mode
+/// widgets to react to the mouse and keyboard.
+///
+/// Tile positions use the same coordinates as [crate::TerminalTransform::world_to_tile],
+/// with the origin at the bottom left of the terminal.
+///
+/// # Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_ascii_terminal::{widget::*, *};
+///
+/// fn ui(
+///     mut q_term: Query<(Entity, &mut Terminal)>,
+///     mouse: Res<TerminalMouse>,
+///     mut mouse_events: EventReader<TerminalMouseEvent>,
+///     keys: Res<ButtonInput<KeyCode>>,
+/// ) {
+///     let events: Vec<_> = mouse_events.read().collect();
+///     for (entity, mut term) in &mut q_term {
+///         let input = WidgetInput::new(entity, &mouse, events.iter().copied(), &keys);
+///         let rect = GridRect::new([1, 1], [10, 1]);
+///         if button(&mut term, rect, "Click me", &input, &WidgetStyle::default()) {
+///             info!("Clicked!");
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WidgetInput {
+    /// The tile under the cursor.
+    pub hovered: Option<IVec2>,
+    /// The tile where the left mouse button was pressed, if it's held down.
+    pub pressed: Option<IVec2>,
+    /// Tiles that were clicked with the left mouse button this frame.
+    pub clicks: Vec<IVec2>,
+    /// Tiles that were double clicked with the left mouse button this frame.
+    pub double_clicks: Vec<IVec2>,
+    /// Vertical mouse wheel movement and the tile it occurred over.
+    pub wheel: Vec<(IVec2, f32)>,
+    /// Keys which were pressed this frame.
+    pub keys: Vec<KeyCode>,
+}
+
+impl WidgetInput {
+    /// Gather the input for a terminal from the [TerminalMouse] resource and
+    /// this frame's [TerminalMouseEvent]s.
+    pub fn new<'a>(
+        terminal: Entity,
+        mouse: &TerminalMouse,
+        events: impl IntoIterator<Item = &'a TerminalMouseEvent>,
+        keys: &ButtonInput<KeyCode>,
+    ) -> Self {
+        let on_terminal = |(e, p): (Entity, IVec2)| (e == terminal).then_some(p);
+        let mut input = Self {
+            hovered: mouse.hovered().and_then(on_terminal),
+            pressed: mouse.pressed(MouseButton::Left).and_then(on_terminal),
+            keys: keys.get_just_pressed().copied().collect(),
+            ..Default::default()
+        };
+        for evt in events.into_iter().filter(|e| e.entity == terminal) {
+            match evt.kind {
+                TerminalMouseEventKind::Click(MouseButton::Left) => input.clicks.push(evt.tile),
+                TerminalMouseEventKind::DoubleClick(MouseButton::Left) => {
+                    input.double_clicks.push(evt.tile)
+                }
+                TerminalMouseEventKind::Wheel(delta) => input.wheel.push((evt.tile, delta.y)),
+                _ => {}
+            }
+        }
+        input
+    }
+
+    /// True if the cursor is over the given area.
+    pub fn is_hovered(&self, rect: GridRect) -> bool {
+        self.hovered.is_some_and(|p| rect.contains_point(p))
+    }
+
+    /// True if the left mouse button was pressed inside the given area and
+    /// is still held down.
+    pub fn is_pressed(&self, rect: GridRect) -> bool {
+        self.pressed.is_some_and(|p| rect.contains_point(p))
+    }
+
+    /// The first tile clicked inside the given area this frame.
+    pub fn clicked(&self, rect: GridRect) -> Option<IVec2> {
+        self.clicks
+            .iter()
+            .copied()
+            .find(|p| rect.contains_point(*p))
+    }
+
+    /// The first tile double clicked inside the given area this frame.
+    pub fn double_clicked(&self, rect: GridRect) -> Option<IVec2> {
+        self.double_clicks
+            .iter()
+            .copied()
+            .find(|p| rect.contains_point(*p))
+    }
+
+    /// The total vertical mouse wheel movement over the given area.
+    pub fn wheel(&self, rect: GridRect) -> f32 {
+        self.wheel
+            .iter()
+            .filter(|(p, _)| rect.contains_point(*p))
+            .map(|(_, d)| d)
+            .sum()
+    }
+
+    /// True if the key was pressed this frame.
+    pub fn key_pressed(&self, key: KeyCode) -> bool {
+        self.keys.contains(&key)
+    }
+
+    /// Simulate a left click on a tile, mainly useful for testing.
+    pub fn click(mut self, tile: impl GridPoint) -> Self {
+        self.hovered = Some(tile.to_ivec2());
+        self.clicks.push(tile.to_ivec2());
+        self
+    }
+}
@@ -0,0 +1,391 @@
+//! A scrollable log of colored messages.
+
+use std::collections::VecDeque;
+
+use bevy::{
+    color::Mix,
+    ecs::{
+        change_detection::{DetectChanges, DetectChangesMut},
+        component::Component,
+        entity::Entity,
+        event::EventReader,
+        system::{Query, Res},
+    },
+    input::{keyboard::KeyCode, ButtonInput},
+};
+use sark_grids::GridRect;
+
+use crate::{
+    string::{line_count, DecoratedString, StringDecoration, StringIter},
+    Terminal, TerminalFocus, TerminalMouseEvent, TerminalMouseEventKind,
+};
+
+use super::clip_rect;
+
+/// The number of lines scrolled per mouse wheel step.
+const WHEEL_SCROLL_LINES: usize = 3;
+
+/// A component which owns a history of messages and draws them into a
+/// region of a terminal, with the newest message at the bottom.
+///
+/// Messages are word wrapped to the width of the region. Repeated messages can
+/// be collapsed into a single line with a count, and older messages are faded
+/// towards the terminal's background color. The log can be scrolled back with
+/// the mouse wheel over the region or with the page up and page down keys.
+///
+/// # Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_ascii_terminal::{widget::MessageLog, *};
+///
+/// fn setup(mut commands: Commands) {
+///     let term = commands.spawn(Terminal::new([30, 8])).id();
+///     let mut log = MessageLog::new(term);
+///     log.push("Welcome to the dungeon!".fg(color::GOLD));
+///     commands.spawn(log);
+/// }
+///
+/// fn attack(mut q_log: Query<&mut MessageLog>) {
+///     for mut log in &mut q_log {
+///         log.push("You hit the rat".fg(color::RED));
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct MessageLog {
+    /// The terminal the log is drawn to.
+    pub terminal: Entity,
+    /// The region of the terminal the log is drawn in. If `None` the log will
+    /// fill the entire terminal.
+    pub rect: Option<GridRect>,
+    /// The maximum number of messages kept in the history. The oldest messages
+    /// are removed once this is exceeded.
+    pub max_messages: usize,
+    /// If true, pushing a message identical to the previous one increases
+    /// the previous message's count instead of adding a new line.
+    pub collapse_repeats: bool,
+    /// How much each message is faded towards the background color, per
+    /// message older than the newest one.
+    pub fade: f32,
+    /// The maximum amount a message can be faded, from 0 to 1.
+    pub max_fade: f32,
+    /// If true, the page up and page down keys scroll the log.
+    pub scroll_keys: bool,
+    messages: VecDeque<LogMessage>,
+    /// The number of lines scrolled back from the newest message.
+    scroll: usize,
+}
+
+/// A single message in a [MessageLog].
+#[derive(Debug, Clone)]
+pub struct LogMessage {
+    pub text: String,
+    pub decoration: StringDecoration,
+    /// The number of times the message was repeated in a row.
+    pub count: usize,
+}
+
+impl LogMessage {
+    /// The text of the message including it's repeat count.
+    pub fn display_text(&self) -> String {
+        if self.count > 1 {
+            format!("{} x{}", self.text, self.count)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+impl MessageLog {
+    pub fn new(terminal: Entity) -> Self {
+        Self {
+            terminal,
+            rect: None,
+            max_messages: 200,
+            collapse_repeats: true,
+            fade: 0.1,
+            max_fade: 0.7,
+            scroll_keys: true,
+            messages: VecDeque::new(),
+            scroll: 0,
+        }
+    }
+
+    pub fn with_rect(mut self, rect: GridRect) -> Self {
+        self.rect = Some(rect);
+        self
+    }
+
+    pub fn with_max_messages(mut self, max_messages: usize) -> Self {
+        self.max_messages = max_messages;
+        self
+    }
+
+    /// Set how much older messages fade per message, and the maximum amount
+    /// they can fade. A fade of 0 disables fading.
+    pub fn with_fade(mut self, fade: f32, max_fade: f32) -> Self {
+        self.fade = fade;
+        self.max_fade = max_fade;
+        self
+    }
+
+    /// Add a message to the log. Colors set on the string are preserved
+    /// when the message is drawn.
+    pub fn push<T: AsRef<str>>(&mut self, message: impl Into<DecoratedString<T>>) {
+        let message: DecoratedString<T> = message.into();
+        let text = message.string.as_ref();
+        if self.collapse_repeats {
+            if let Some(last) = self.messages.back_mut().filter(|m| m.text == text) {
+                last.count += 1;
+                last.decoration = message.decoration;
+                return;
+            }
+        }
+        self.messages.push_back(LogMessage {
+            text: text.to_string(),
+            decoration: message.decoration,
+            count: 1,
+        });
+        while self.messages.len() > self.max_messages {
+            self.messages.pop_front();
+        }
+    }
+
+    /// The messages in the log, from oldest to newest.
+    pub fn messages(&self) -> impl DoubleEndedIterator<Item = &LogMessage> {
+        self.messages.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+        self.scroll = 0;
+    }
+
+    /// The number of lines the log is scrolled back from the newest message.
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// Scroll back towards older messages. The scroll position is clamped
+    /// when the log is drawn.
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_add(lines);
+    }
+
+    /// Scroll forward towards the newest message.
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = 0;
+    }
+
+    /// The region of the terminal the log is drawn in. This is empty if the
+    /// rect is outside the terminal.
+    pub fn region(&self, term: &Terminal) -> GridRect {
+        self.rect
+            .map_or(term.bounds(), |r| clip_rect(r, term.bounds()))
+    }
+
+    /// The total number of wrapped lines in the log for the given width.
+    pub fn line_count(&self, width: usize) -> usize {
+        if width == 0 {
+            return 0;
+        }
+        self.messages
+            .iter()
+            .map(|m| line_count(&m.display_text(), width, true).max(1))
+            .sum()
+    }
+
+    /// Clear the log's region of the terminal and draw the visible messages.
+    pub fn draw(&mut self, term: &mut Terminal) {
+        let region = self.region(term);
+        let (width, height) = (region.width(), region.height());
+        if width == 0 || height == 0 {
+            return;
+        }
+        let clear_tile = term.clear_tile();
+        for p in region.iter_points() {
+            term.put_tile(p, clear_tile);
+        }
+        let max_scroll = self.line_count(width).saturating_sub(height);
+        self.scroll = self.scroll.min(max_scroll);
+
+        // Lines are counted up from the bottom row of the region
+        let mut line = -(self.scroll as i32);
+        for (age, message) in self.messages.iter().rev().enumerate() {
+            if line >= height as i32 {
+                break;
+            }
+            let text = message.display_text();
+            let lines = line_count(&text, width, true).max(1);
+            let rect = GridRect::new([region.left(), region.bottom() + line], [width, lines]);
+            line += lines as i32;
+            if rect.top() < region.bottom() {
+                continue;
+            }
+
+            let fade = (age as f32 * self.fade).clamp(0.0, self.max_fade.clamp(0.0, 1.0));
+            let fg = message
+                .decoration
+                .fg_color
+                .unwrap_or(clear_tile.fg_color)
+                .mix(&clear_tile.bg_color, fade);
+            let decoration = StringDecoration {
+                fg_color: Some(fg),
+                ..message.decoration
+            };
+            for (p, (ch, fg, bg)) in StringIter::new(&text, rect, [0, 0], None, Some(decoration)) {
+                if !region.contains_point(p) {
+                    continue;
+                }
+                let tile = term.tile_mut(p);
+                tile.glyph = ch;
+                if let Some(fg) = fg {
+                    tile.fg_color = fg;
+                }
+                if let Some(bg) = bg {
+                    tile.bg_color = bg;
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn scroll_message_log(
+    mut q_log: Query<&mut MessageLog>,
+    q_term: Query<&Terminal>,
+    mut mouse_events: EventReader<TerminalMouseEvent>,
+    keys: Res<ButtonInput<KeyCode>>,
+    focus: Res<TerminalFocus>,
+) {
+    let wheel: Vec<_> = mouse_events
+        .read()
+        .filter_map(|e| match e.kind {
+            TerminalMouseEventKind::Wheel(delta) if delta.y != 0.0 => {
+                Some((e.entity, e.tile, delta.y))
+            }
+            _ => None,
+        })
+        .collect();
+    let page_up = keys.just_pressed(KeyCode::PageUp);
+    let page_down = keys.just_pressed(KeyCode::PageDown);
+    if wheel.is_empty() && !page_up && !page_down {
+        return;
+    }
+    for mut log in &mut q_log {
+        let Ok(term) = q_term.get(log.terminal) else {
+            continue;
+        };
+        let (terminal, region) = (log.terminal, log.region(term));
+        for (_, _, delta) in wheel
+            .iter()
+            .filter(|(e, p, _)| *e == terminal && region.contains_point(*p))
+        {
+            if *delta > 0.0 {
+                log.scroll_up(WHEEL_SCROLL_LINES);
+            } else {
+                log.scroll_down(WHEEL_SCROLL_LINES);
+            }
+        }
+        if log.scroll_keys && focus.has_focus(terminal) {
+            let page = region.height().saturating_sub(1).max(1);
+            if page_up {
+                log.scroll_up(page);
+            }
+            if page_down {
+                log.scroll_down(page);
+            }
+        }
+    }
+}
+
+pub(super) fn draw_message_log(
+    mut q_log: Query<&mut MessageLog>,
+    mut q_term: Query<&mut Terminal>,
+) {
+    for mut log in &mut q_log {
+        let Ok(mut term) = q_term.get_mut(log.terminal) else {
+            continue;
+        };
+        // Redraw if the terminal was modified elsewhere, ie: cleared
+        if !log.is_changed() && !term.is_changed() {
+            continue;
+        }
+        // Clamping the scroll position shouldn't trigger another redraw
+        log.bypass_change_detection().draw(&mut term);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{color, StringDecorator};
+
+    use super::*;
+
+    fn read_row(term: &Terminal, y: i32) -> String {
+        (0..term.width() as i32)
+            .map(|x| term.tile([x, y]).glyph)
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    #[test]
+    fn collapse_repeats() {
+        let mut log = MessageLog::new(Entity::PLACEHOLDER);
+        log.push("You hit the rat");
+        log.push("You hit the rat");
+        log.push("You hit the rat".fg(color::RED));
+        log.push("The rat dies");
+        let text: Vec<_> = log.messages().map(|m| m.display_text()).collect();
+        assert_eq!(vec!["You hit the rat x3", "The rat dies"], text);
+    }
+
+    #[test]
+    fn wrap_and_scroll() {
+        let mut term = Terminal::new([10, 3]);
+        let mut log = MessageLog::new(Entity::PLACEHOLDER);
+        log.push("First");
+        log.push("A much longer message");
+        log.push("Last");
+        assert_eq!(5, log.line_count(10));
+
+        log.draw(&mut term);
+        assert_eq!("longer", read_row(&term, 2));
+        assert_eq!("message", read_row(&term, 1));
+        assert_eq!("Last", read_row(&term, 0));
+
+        log.scroll_up(100);
+        log.draw(&mut term);
+        assert_eq!(2, log.scroll());
+        assert_eq!("First", read_row(&term, 2));
+        assert_eq!("A much", read_row(&term, 1));
+        assert_eq!("longer", read_row(&term, 0));
+    }
+
+    #[test]
+    fn fade_and_colors() {
+        let mut term = Terminal::new([10, 2]);
+        let mut log = MessageLog::new(Entity::PLACEHOLDER).with_fade(0.5, 0.5);
+        log.push("Old".fg(color::RED));
+        log.push("New".fg(color::RED));
+        log.draw(&mut term);
+        assert_eq!(color::RED, term.tile([0, 0]).fg_color);
+        let faded = color::RED.mix(&term.clear_tile().bg_color, 0.5);
+        assert_eq!(faded, term.tile([0, 1]).fg_color);
+    }
+
+    #[test]
+    fn rect_outside_terminal() {
+        let mut term = Terminal::new([4, 2]);
+        let mut log = MessageLog::new(Entity::PLACEHOLDER).with_rect(GridRect::new([6, 2], [4, 2]));
+        log.push("Hello");
+        assert_eq!(0, log.region(&term).width());
+        log.draw(&mut term);
+        assert_eq!("", read_row(&term, 1));
+    }
+}
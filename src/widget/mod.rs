@@ -0,0 +1,84 @@
+//! Immediate and retained mode widgets drawn into terminals.
+
+mod controls;
+mod input;
+mod message_log;
+mod popup;
+mod scroll_view;
+mod text_input;
+mod tooltip;
+mod typewriter;
+
+use bevy::{
+    app::{Plugin, PostUpdate, PreUpdate},
+    ecs::schedule::{IntoSystemConfigs, SystemSet},
+    input::InputSystem,
+};
+pub use controls::{
+    button, checkbox, list, progress_bar, radio_group, slider, ListResponse, ListState, WidgetStyle,
+};
+pub use input::WidgetInput;
+pub use message_log::{LogMessage, MessageLog};
+pub use popup::{Popup, PopupClosed, PopupKind, PopupResult};
+pub use scroll_view::ScrollView;
+pub use text_input::{
+    TerminalTextInput, TextInput, TextInputAction, TextInputModifiers, TextInputStyle,
+    TextInputSubmitted,
+};
+pub use tooltip::Tooltip;
+pub use typewriter::{Typewriter, TypewriterFinished};
+
+use crate::{
+    mouse::TerminalSystemsMouseInput, render::TerminalSystemsUpdateMesh,
+    transform::TerminalSystemsUpdateTransform, GridRect,
+};
+
+pub(crate) struct TerminalWidgetPlugin;
+
+/// Systems for applying input to terminal widgets. Runs in [PreUpdate].
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, SystemSet)]
+pub struct TerminalSystemsWidgetInput;
+
+impl Plugin for TerminalWidgetPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_event::<TextInputSubmitted>()
+            .add_event::<PopupClosed>()
+            .add_event::<TypewriterFinished>()
+            .add_observer(popup::on_popup_added)
+            .add_systems(
+                PreUpdate,
+                (
+                    text_input::handle_text_input.after(InputSystem),
+                    message_log::scroll_message_log.after(TerminalSystemsMouseInput),
+                    popup::handle_popup_input.after(TerminalSystemsMouseInput),
+                    typewriter::skip_typewriters.after(TerminalSystemsMouseInput),
+                )
+                    .in_set(TerminalSystemsWidgetInput),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    (popup::position_popups, tooltip::update_tooltips)
+                        .before(TerminalSystemsUpdateTransform),
+                    (
+                        text_input::draw_text_input,
+                        message_log::draw_message_log,
+                        popup::draw_popups,
+                        typewriter::update_typewriters,
+                    )
+                        .before(TerminalSystemsUpdateMesh),
+                ),
+            );
+    }
+}
+
+/// The part of a rect inside some bounds, or an empty rect if they don't
+/// overlap. Unlike [GridRect::clipped] this never returns a rect outside the
+/// bounds.
+pub(crate) fn clip_rect(rect: GridRect, bounds: GridRect) -> GridRect {
+    if rect.overlaps_rect(bounds) {
+        rect.clipped(bounds)
+    } else {
+        GridRect::new(bounds.pos, [0, 0])
+    }
+}
@@ -0,0 +1,510 @@
+//! Popup menus and confirm dialogs drawn in overlay terminals.
+
+use std::ops::Range;
+
+use bevy::{
+    ecs::{
+        change_detection::{DetectChanges, DetectChangesMut, Ref},
+        component::Component,
+        entity::Entity,
+        event::{Event, EventReader, EventWriter},
+        observer::Trigger,
+        system::{Commands, Query, Res, ResMut},
+        world::OnAdd,
+    },
+    hierarchy::DespawnRecursiveExt,
+    input::{keyboard::KeyCode, mouse::MouseButton, ButtonInput},
+    math::{IVec2, UVec2},
+    transform::components::GlobalTransform,
+};
+
+use crate::{
+    render::TerminalMeshPivot,
+    transform::{SetTerminalGridPosition, SetTerminalLayerPosition, TerminalTransform},
+    Terminal, TerminalBorder, TerminalFocus, TerminalMouse, TerminalMouseEvent,
+    TerminalMouseEventKind,
+};
+
+use super::WidgetStyle;
+
+/// A popup menu or confirm dialog.
+///
+/// Adding this component to an entity turns it into an overlay terminal sized
+/// to fit it's content, with a [TerminalBorder] if it doesn't already have
+/// one. The popup captures [TerminalFocus] until it's closed, so other
+/// terminals won't receive mouse events or keyboard input for built in widgets.
+///
+/// A popup can be anchored to a tile of another terminal with [Popup::at], in
+/// which case it's positioned with it's top left corner at the bottom left of
+/// that tile and on a layer above that terminal.
+///
+/// Options are chosen with the arrow keys and enter or by clicking them.
+/// Pressing escape, or clicking outside of a menu, cancels the popup. Once
+/// closed the popup entity is despawned and a [PopupClosed] event is sent.
+///
+/// # Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_ascii_terminal::{widget::*, *};
+///
+/// fn open_menu(
+///     mut commands: Commands,
+///     q_term: Query<Entity, With<Terminal>>,
+///     mouse: Res<TerminalMouse>,
+///     buttons: Res<ButtonInput<MouseButton>>,
+/// ) {
+///     if !buttons.just_pressed(MouseButton::Right) {
+///         return;
+///     }
+///     if let Some((term, tile)) = mouse.hovered().filter(|(e, _)| q_term.contains(*e)) {
+///         commands.spawn(Popup::menu(["Look", "Take", "Attack"]).at(term, tile));
+///     }
+/// }
+///
+/// fn on_closed(mut evt: EventReader<PopupClosed>) {
+///     for evt in evt.read() {
+///         if let PopupResult::Selected(i) = evt.result {
+///             info!("Selected option {}", i);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+#[require(TerminalMeshPivot(popup_pivot))]
+pub struct Popup {
+    pub kind: PopupKind,
+    /// The terminal and tile the popup is positioned relative to.
+    pub anchor: Option<(Entity, IVec2)>,
+    pub style: WidgetStyle,
+    selected: usize,
+    positioned: bool,
+}
+
+/// The content of a [Popup].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PopupKind {
+    /// A vertical list of options.
+    Menu(Vec<String>),
+    /// A message with a row of buttons below it.
+    Confirm {
+        message: String,
+        buttons: Vec<String>,
+    },
+}
+
+/// How a [Popup] was closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopupResult {
+    /// The option or button at the given index was chosen.
+    Selected(usize),
+    /// The popup was closed without choosing an option.
+    Cancelled,
+}
+
+/// Sent when a [Popup] is closed, before the popup entity is despawned.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct PopupClosed {
+    /// The popup entity.
+    pub entity: Entity,
+    /// The terminal the popup was anchored to, if any.
+    pub anchor: Option<Entity>,
+    pub result: PopupResult,
+}
+
+fn popup_pivot() -> TerminalMeshPivot {
+    TerminalMeshPivot::TopLeft
+}
+
+impl Popup {
+    pub fn new(kind: PopupKind) -> Self {
+        Self {
+            kind,
+            anchor: None,
+            style: WidgetStyle::default(),
+            selected: 0,
+            positioned: false,
+        }
+    }
+
+    /// A popup menu with a list of options.
+    pub fn menu(items: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self::new(PopupKind::Menu(items.into_iter().map(Into::into).collect()))
+    }
+
+    /// A confirm dialog with "Yes" and "No" buttons.
+    pub fn confirm(message: impl Into<String>) -> Self {
+        Self::new(PopupKind::Confirm {
+            message: message.into(),
+            buttons: vec!["Yes".to_string(), "No".to_string()],
+        })
+    }
+
+    /// Replace the buttons of a confirm dialog. Has no effect on menus.
+    pub fn with_buttons(mut self, labels: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        if let PopupKind::Confirm { buttons, .. } = &mut self.kind {
+            *buttons = labels.into_iter().map(Into::into).collect();
+        }
+        self
+    }
+
+    /// Position the popup relative to a tile of another terminal.
+    pub fn at(mut self, terminal: Entity, tile: impl Into<IVec2>) -> Self {
+        self.anchor = Some((terminal, tile.into()));
+        self
+    }
+
+    pub fn with_style(mut self, style: WidgetStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// The index of the highlighted option.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    fn option_count(&self) -> usize {
+        match &self.kind {
+            PopupKind::Menu(items) => items.len(),
+            PopupKind::Confirm { buttons, .. } => buttons.len(),
+        }
+    }
+
+    /// The size of the popup terminal, excluding it's border.
+    pub fn size(&self) -> UVec2 {
+        match &self.kind {
+            PopupKind::Menu(items) => {
+                let width = items.iter().map(|s| s.chars().count()).max().unwrap_or(0);
+                UVec2::new(width as u32 + 2, items.len() as u32)
+            }
+            PopupKind::Confirm { message, .. } => {
+                let lines = message.lines().count();
+                let width = message.lines().map(|l| l.chars().count()).max();
+                let width = width.unwrap_or(0).max(self.buttons_width());
+                UVec2::new(width as u32 + 2, lines as u32 + 2)
+            }
+        }
+        .max(UVec2::new(2, 2))
+    }
+
+    /// The total width of a confirm dialog's buttons.
+    fn buttons_width(&self) -> usize {
+        let PopupKind::Confirm { buttons, .. } = &self.kind else {
+            return 0;
+        };
+        let labels: usize = buttons.iter().map(|b| b.chars().count() + 2).sum();
+        labels + buttons.len().saturating_sub(1)
+    }
+
+    /// The column ranges of a confirm dialog's buttons, centered in the given
+    /// width.
+    fn button_ranges(&self, width: usize) -> Vec<Range<i32>> {
+        let PopupKind::Confirm { buttons, .. } = &self.kind else {
+            return Vec::new();
+        };
+        let mut x = (width.saturating_sub(self.buttons_width()) / 2) as i32;
+        buttons
+            .iter()
+            .map(|b| {
+                let start = x;
+                x += b.chars().count() as i32 + 3;
+                start..start + b.chars().count() as i32 + 2
+            })
+            .collect()
+    }
+
+    /// The option at a tile of the popup terminal.
+    pub fn option_at(&self, size: UVec2, tile: IVec2) -> Option<usize> {
+        let row = size.y as i32 - 1 - tile.y;
+        match &self.kind {
+            PopupKind::Menu(items) => {
+                (tile.x >= 0 && tile.x < size.x as i32 && row >= 0 && (row as usize) < items.len())
+                    .then_some(row as usize)
+            }
+            PopupKind::Confirm { .. } => {
+                if tile.y != 0 {
+                    return None;
+                }
+                self.button_ranges(size.x as usize)
+                    .iter()
+                    .position(|r| r.contains(&tile.x))
+            }
+        }
+    }
+
+    /// Apply a key press, returning a result if the key closed the popup.
+    pub fn handle_key(&mut self, key: KeyCode) -> Option<PopupResult> {
+        let count = self.option_count();
+        let (back, forward) = match self.kind {
+            PopupKind::Menu(_) => (KeyCode::ArrowUp, KeyCode::ArrowDown),
+            PopupKind::Confirm { .. } => (KeyCode::ArrowLeft, KeyCode::ArrowRight),
+        };
+        match key {
+            KeyCode::Escape => return Some(PopupResult::Cancelled),
+            KeyCode::Enter if count > 0 => return Some(PopupResult::Selected(self.selected)),
+            k if k == back => self.selected = self.selected.saturating_sub(1),
+            k if k == forward || k == KeyCode::Tab => {
+                self.selected = (self.selected + 1).min(count.saturating_sub(1))
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Draw the popup content into it's terminal.
+    pub fn draw(&self, term: &mut Terminal) {
+        let style = &self.style;
+        let (width, height) = (term.width(), term.height() as i32);
+        for t in term.iter_mut() {
+            t.glyph = ' ';
+            t.fg_color = style.fg;
+            t.bg_color = style.bg;
+        }
+        match &self.kind {
+            PopupKind::Menu(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    let y = height - 1 - i as i32;
+                    if i == self.selected {
+                        for x in 0..width as i32 {
+                            term.put_bg_color([x, y], style.selected_bg);
+                        }
+                    }
+                    put_text(term, IVec2::new(1, y), item);
+                }
+            }
+            PopupKind::Confirm { message, buttons } => {
+                for (i, line) in message.lines().enumerate() {
+                    let y = height - 1 - i as i32;
+                    let x = (width.saturating_sub(line.chars().count()) / 2) as i32;
+                    put_text(term, IVec2::new(x, y), line);
+                }
+                for (i, (label, range)) in buttons.iter().zip(self.button_ranges(width)).enumerate()
+                {
+                    let bg = if i == self.selected {
+                        style.selected_bg
+                    } else {
+                        style.hover_bg
+                    };
+                    for x in range.clone() {
+                        if x < width as i32 {
+                            term.put_bg_color([x, 0], bg);
+                        }
+                    }
+                    put_text(term, IVec2::new(range.start + 1, 0), label);
+                }
+            }
+        }
+    }
+}
+
+/// Write a line of text, clipped to the terminal bounds.
+fn put_text(term: &mut Terminal, xy: IVec2, text: &str) {
+    for (i, ch) in text.chars().enumerate() {
+        let p = xy + IVec2::new(i as i32, 0);
+        if term.bounds().contains_point(p) {
+            term.put_char(p, ch);
+        }
+    }
+}
+
+pub(super) fn on_popup_added(
+    trigger: Trigger<OnAdd, Popup>,
+    q_popup: Query<(&Popup, Option<&TerminalBorder>)>,
+    mut focus: ResMut<TerminalFocus>,
+    mut commands: Commands,
+) {
+    let entity = trigger.entity();
+    let Ok((popup, border)) = q_popup.get(entity) else {
+        return;
+    };
+    let mut term = Terminal::new(popup.size());
+    popup.draw(&mut term);
+    let mut entity_commands = commands.entity(entity);
+    entity_commands.insert(term);
+    if border.is_none() {
+        entity_commands.insert(TerminalBorder::single_line());
+    }
+    focus.push(entity);
+}
+
+/// Move anchored popups next to their anchor tile once the anchor terminal's
+/// transform data is available.
+pub(super) fn position_popups(
+    mut q_popup: Query<(Entity, &mut Popup)>,
+    q_anchor: Query<(&TerminalTransform, &GlobalTransform)>,
+    mut commands: Commands,
+) {
+    for (entity, mut popup) in &mut q_popup {
+        if popup.positioned {
+            continue;
+        }
+        let Some((anchor, tile)) = popup.anchor else {
+            continue;
+        };
+        let Some((data, transform)) = q_anchor
+            .get(anchor)
+            .ok()
+            .and_then(|(t, gt)| t.cached_data.as_ref().map(|d| (d, gt)))
+        else {
+            continue;
+        };
+        let origin = data.world_pos.truncate() + data.local_inner_mesh_bounds.min;
+        let grid_pos = (origin / data.world_tile_size).round().as_ivec2() + tile;
+        let layer = transform.translation().z.round() as i32 + 1;
+        commands.entity(entity).insert((
+            SetTerminalGridPosition(grid_pos),
+            SetTerminalLayerPosition(layer),
+        ));
+        popup.bypass_change_detection().positioned = true;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn handle_popup_input(
+    mut q_popup: Query<(Entity, &mut Popup, &Terminal)>,
+    mut mouse_events: EventReader<TerminalMouseEvent>,
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mouse: Res<TerminalMouse>,
+    mut focus: ResMut<TerminalFocus>,
+    mut evt: EventWriter<PopupClosed>,
+    mut commands: Commands,
+) {
+    let Some((entity, mut popup, term)) = focus.focused().and_then(|e| q_popup.get_mut(e).ok())
+    else {
+        return;
+    };
+    // Ignore the input that opened the popup
+    if popup.is_added() {
+        mouse_events.clear();
+        return;
+    }
+    let mut result = None;
+    for key in keys.get_just_pressed() {
+        result = result.or_else(|| popup.handle_key(*key));
+    }
+    for e in mouse_events.read().filter(|e| e.entity == entity) {
+        let Some(i) = popup.option_at(term.size(), e.tile) else {
+            continue;
+        };
+        match e.kind {
+            TerminalMouseEventKind::Enter if popup.selected != i => popup.selected = i,
+            TerminalMouseEventKind::Click(MouseButton::Left) => {
+                result = result.or(Some(PopupResult::Selected(i)))
+            }
+            _ => {}
+        }
+    }
+    // Clicking outside of a menu closes it, dialogs are modal
+    let outside = mouse.hovered().is_none_or(|(e, _)| e != entity);
+    if matches!(popup.kind, PopupKind::Menu(_))
+        && outside
+        && buttons.just_pressed(MouseButton::Left)
+    {
+        result = result.or(Some(PopupResult::Cancelled));
+    }
+
+    if let Some(result) = result {
+        evt.send(PopupClosed {
+            entity,
+            anchor: popup.anchor.map(|(e, _)| e),
+            result,
+        });
+        focus.remove(entity);
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub(super) fn draw_popups(mut q_popup: Query<(Ref<Popup>, &mut Terminal)>) {
+    for (popup, mut term) in &mut q_popup {
+        if popup.is_changed() || term.is_changed() {
+            popup.draw(&mut term);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn menu() {
+        let mut popup = Popup::menu(["Look", "Take", "Attack"]);
+        assert_eq!(UVec2::new(8, 3), popup.size());
+        assert_eq!(None, popup.handle_key(KeyCode::ArrowDown));
+        popup.handle_key(KeyCode::ArrowDown);
+        popup.handle_key(KeyCode::ArrowDown);
+        assert_eq!(2, popup.selected());
+        assert_eq!(
+            Some(PopupResult::Selected(2)),
+            popup.handle_key(KeyCode::Enter)
+        );
+        // Rows are counted from the top of the terminal
+        assert_eq!(Some(0), popup.option_at(popup.size(), IVec2::new(3, 2)));
+        assert_eq!(None, popup.option_at(popup.size(), IVec2::new(3, 3)));
+    }
+
+    #[test]
+    fn confirm() {
+        let mut popup = Popup::confirm("Quit?");
+        // " Yes " + " " + " No "
+        assert_eq!(UVec2::new(12, 3), popup.size());
+        let mut term = Terminal::new(popup.size());
+        popup.draw(&mut term);
+        let row: String = term.iter_row(0).map(|t| t.glyph).collect();
+        assert_eq!("  Yes   No  ", row);
+        assert_eq!(Some(1), popup.option_at(popup.size(), IVec2::new(8, 0)));
+        popup.handle_key(KeyCode::ArrowRight);
+        assert_eq!(1, popup.selected());
+        assert_eq!(
+            Some(PopupResult::Cancelled),
+            popup.handle_key(KeyCode::Escape)
+        );
+    }
+
+    #[test]
+    fn ignore_opening_click() {
+        use bevy::{
+            app::{App, PreUpdate},
+            ecs::{event::Events, schedule::IntoSystemConfigs},
+            input::{mouse::MouseButtonInput, ButtonState, InputPlugin, InputSystem},
+            MinimalPlugins,
+        };
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin))
+            .init_resource::<TerminalFocus>()
+            .init_resource::<TerminalMouse>()
+            .add_event::<TerminalMouseEvent>()
+            .add_event::<PopupClosed>()
+            .add_systems(PreUpdate, handle_popup_input.after(InputSystem));
+        let click = |app: &mut App| {
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                app.world_mut().send_event(MouseButtonInput {
+                    button: MouseButton::Left,
+                    state,
+                    window: Entity::PLACEHOLDER,
+                });
+                app.update();
+            }
+        };
+        app.update();
+
+        let popup = Popup::menu(["Look", "Take"]);
+        let term = Terminal::new(popup.size());
+        let entity = app.world_mut().spawn((popup, term)).id();
+        app.world_mut().resource_mut::<TerminalFocus>().push(entity);
+        click(&mut app);
+        assert!(app.world().get_entity(entity).is_ok());
+
+        click(&mut app);
+        let closed: Vec<_> = app
+            .world_mut()
+            .resource_mut::<Events<PopupClosed>>()
+            .drain()
+            .map(|e| e.result)
+            .collect();
+        assert_eq!(vec![PopupResult::Cancelled], closed);
+    }
+}
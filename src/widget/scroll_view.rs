@@ -0,0 +1,432 @@
+//! A scrollable view of a canvas larger than the area it's drawn in.
+
+use std::ops::Range;
+
+use bevy::math::{IVec2, UVec2};
+use sark_grids::{GridRect, GridSize};
+
+use crate::{ascii::Glyph, Terminal};
+
+use super::{clip_rect, WidgetInput, WidgetStyle};
+
+/// The number of tiles scrolled per mouse wheel step.
+const WHEEL_SCROLL_TILES: f32 = 3.0;
+
+/// A virtual canvas which can be larger than the area it's drawn in, with
+/// scrollbars for any axis where the canvas doesn't fit.
+///
+/// Content is written to the canvas, which is a regular [Terminal], and the
+/// visible part of it is copied into a [GridRect] of another terminal by
+/// [ScrollView::draw]. The view can be scrolled with the mouse wheel, by
+/// clicking the scrollbar arrows or by dragging along a scrollbar.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ascii_terminal::{widget::*, *};
+///
+/// let mut view = ScrollView::new([20, 50]);
+/// for i in 0..50 {
+///     view.canvas_mut().put_string([0, i], format!("Line {}", i));
+/// }
+///
+/// let mut term = Terminal::new([20, 10]);
+/// let rect = term.bounds();
+/// view.scroll_to_row(30);
+/// view.draw(&mut term, rect, &WidgetInput::default(), &WidgetStyle::default());
+/// assert!(view.visible_rows().contains(&30));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScrollView {
+    canvas: Terminal,
+    /// The scroll position in tiles, as columns from the left and rows from
+    /// the top of the canvas.
+    scroll: UVec2,
+    /// The size of the area the canvas was last drawn in, excluding scrollbars.
+    viewport: UVec2,
+}
+
+impl ScrollView {
+    /// Create a scroll view with an empty canvas of the given size.
+    pub fn new(canvas_size: impl GridSize) -> Self {
+        Self::from_canvas(Terminal::new(canvas_size))
+    }
+
+    /// Create a scroll view for an existing canvas.
+    pub fn from_canvas(canvas: Terminal) -> Self {
+        Self {
+            canvas,
+            scroll: UVec2::ZERO,
+            viewport: UVec2::ZERO,
+        }
+    }
+
+    pub fn canvas(&self) -> &Terminal {
+        &self.canvas
+    }
+
+    pub fn canvas_mut(&mut self) -> &mut Terminal {
+        &mut self.canvas
+    }
+
+    /// The scroll position as columns from the left and rows from the top of
+    /// the canvas.
+    pub fn scroll(&self) -> UVec2 {
+        self.scroll
+    }
+
+    /// Set the scroll position as columns from the left and rows from the top
+    /// of the canvas. The position is clamped when the view is drawn.
+    pub fn set_scroll(&mut self, scroll: impl GridSize) {
+        self.scroll = scroll.to_uvec2();
+    }
+
+    /// Scroll by the given number of tiles. Positive values scroll right and
+    /// down.
+    pub fn scroll_by(&mut self, delta: impl Into<IVec2>) {
+        let scroll = self.scroll.as_ivec2() + delta.into();
+        self.scroll = scroll.max(IVec2::ZERO).as_uvec2();
+        self.clamp_scroll();
+    }
+
+    /// Scroll the minimum amount so the given row, counted from the top of the
+    /// canvas, is visible.
+    pub fn scroll_to_row(&mut self, row: usize) {
+        let row = row as u32;
+        let height = self.viewport.y.max(1);
+        if row < self.scroll.y {
+            self.scroll.y = row;
+        } else if row >= self.scroll.y + height {
+            self.scroll.y = row + 1 - height;
+        }
+    }
+
+    /// The range of canvas rows, counted from the top, which were visible
+    /// when the view was last drawn.
+    pub fn visible_rows(&self) -> Range<usize> {
+        let start = self.scroll.y as usize;
+        start..(start + self.viewport.y as usize).min(self.canvas.height())
+    }
+
+    /// The range of canvas columns which were visible when the view was last
+    /// drawn.
+    pub fn visible_columns(&self) -> Range<usize> {
+        let start = self.scroll.x as usize;
+        start..(start + self.viewport.x as usize).min(self.canvas.width())
+    }
+
+    /// The area of the canvas, in canvas tile coordinates, which was visible
+    /// when the view was last drawn.
+    pub fn visible_rect(&self) -> GridRect {
+        let (rows, columns) = (self.visible_rows(), self.visible_columns());
+        let bottom = self.canvas.height() - rows.end;
+        GridRect::new([columns.start, bottom], [columns.len(), rows.len()])
+    }
+
+    fn max_scroll(&self) -> UVec2 {
+        self.canvas.size().saturating_sub(self.viewport)
+    }
+
+    fn clamp_scroll(&mut self) {
+        self.scroll = self.scroll.min(self.max_scroll());
+    }
+
+    /// Draw the visible part of the canvas into a rect of the terminal, with
+    /// scrollbars along the right and bottom edges of the rect if the canvas
+    /// doesn't fit. Scrolling input over the rect is applied first.
+    ///
+    /// Returns true if the scroll position changed.
+    pub fn draw(
+        &mut self,
+        term: &mut Terminal,
+        rect: GridRect,
+        input: &WidgetInput,
+        style: &WidgetStyle,
+    ) -> bool {
+        let rect = clip_rect(rect, term.bounds());
+        if rect.width() == 0 || rect.height() == 0 {
+            return false;
+        }
+        let canvas = self.canvas.size();
+        // Each scrollbar takes up space which might make the other necessary
+        let mut vertical = canvas.y > rect.size.y;
+        let horizontal = canvas.x > rect.size.x.saturating_sub(vertical as u32);
+        vertical |= canvas.y > rect.size.y.saturating_sub(horizontal as u32);
+        self.viewport = rect
+            .size
+            .saturating_sub(UVec2::new(vertical as u32, horizontal as u32));
+        let previous = self.scroll;
+
+        let view = GridRect::new(
+            [rect.left(), rect.top() + 1 - self.viewport.y as i32],
+            self.viewport,
+        );
+        let wheel = input.wheel(rect);
+        if wheel != 0.0 {
+            let delta = (-wheel * WHEEL_SCROLL_TILES).round() as i32;
+            if vertical {
+                self.scroll_by([0, delta]);
+            } else if horizontal {
+                self.scroll_by([delta, 0]);
+            }
+        }
+        let bars = [(vertical, Axis::Vertical), (horizontal, Axis::Horizontal)];
+        for (_, axis) in bars.iter().filter(|(visible, _)| *visible) {
+            let bar = axis.bar_rect(view);
+            let track = Track::new(bar.size[axis.index()] as usize);
+            let arrow = input
+                .clicked(bar)
+                .and_then(|p| track.arrow_at(axis.track_pos(bar, p)));
+            if let Some(dir) = arrow {
+                self.scroll_by(axis.delta(dir));
+            }
+            if input.is_pressed(bar) {
+                let Some(p) = input.hovered else {
+                    continue;
+                };
+                let pos = axis.track_pos(bar, p);
+                let pressed = input.pressed.map(|p| axis.track_pos(bar, p));
+                // Dragging starts on the track rather than the arrows
+                if pressed.is_some_and(|p| track.arrow_at(p).is_none()) {
+                    let i = axis.index();
+                    self.scroll[i] =
+                        track.scroll_at(pos, self.viewport[i] as usize, canvas[i] as usize) as u32;
+                }
+            }
+        }
+        self.clamp_scroll();
+
+        // Copy the visible part of the canvas
+        let columns = self.visible_columns();
+        for (y, row) in self.visible_rows().enumerate() {
+            let canvas_y = self.canvas.height() - 1 - row;
+            for (x, column) in columns.clone().enumerate() {
+                let tile = *self.canvas.tile([column, canvas_y]);
+                term.put_tile([view.left() + x as i32, view.top() - y as i32], tile);
+            }
+        }
+        // Clear any part of the view the canvas doesn't cover
+        for p in view.iter_points() {
+            let local = p - view.top_left();
+            if local.x >= columns.len() as i32 || -local.y >= self.visible_rows().len() as i32 {
+                term.put_tile(p, term.clear_tile());
+            }
+        }
+
+        for (_, axis) in bars.iter().filter(|(visible, _)| *visible) {
+            let i = axis.index();
+            axis.draw(
+                term,
+                axis.bar_rect(view),
+                self.scroll[i] as usize,
+                self.viewport[i] as usize,
+                canvas[i] as usize,
+                style,
+            );
+        }
+        if vertical && horizontal {
+            term.put_char([rect.right(), rect.bottom()], ' ')
+                .fg(style.fg)
+                .bg(style.bg);
+        }
+        self.scroll != previous
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Vertical,
+    Horizontal,
+}
+
+impl Axis {
+    /// The index of this axis in a vector.
+    fn index(self) -> usize {
+        match self {
+            Axis::Vertical => 1,
+            Axis::Horizontal => 0,
+        }
+    }
+
+    /// The scrollbar rect alongside a view.
+    fn bar_rect(self, view: GridRect) -> GridRect {
+        match self {
+            Axis::Vertical => GridRect::new([view.right() + 1, view.bottom()], [1, view.height()]),
+            Axis::Horizontal => GridRect::new([view.left(), view.bottom() - 1], [view.width(), 1]),
+        }
+    }
+
+    /// A tile's position along a scrollbar, from the top or left. May be out
+    /// of range if the tile is outside the bar.
+    fn track_pos(self, bar: GridRect, p: IVec2) -> i32 {
+        match self {
+            Axis::Vertical => bar.top() - p.y,
+            Axis::Horizontal => p.x - bar.left(),
+        }
+    }
+
+    /// The scroll delta for one step in a direction along the axis, where
+    /// negative is up or left.
+    fn delta(self, dir: i32) -> IVec2 {
+        match self {
+            Axis::Vertical => IVec2::new(0, dir),
+            Axis::Horizontal => IVec2::new(dir, 0),
+        }
+    }
+
+    fn draw(
+        self,
+        term: &mut Terminal,
+        bar: GridRect,
+        scroll: usize,
+        view: usize,
+        content: usize,
+        style: &WidgetStyle,
+    ) {
+        let track = Track::new(bar.size[self.index()] as usize);
+        let (back, forward) = match self {
+            Axis::Vertical => (Glyph::TriangleUp, Glyph::TriangleDown),
+            Axis::Horizontal => (Glyph::TriangleLeft, Glyph::TriangleRight),
+        };
+        let thumb = track.thumb(scroll, view, content);
+        for i in 0..track.len {
+            let p = match self {
+                Axis::Vertical => IVec2::new(bar.left(), bar.top() - i as i32),
+                Axis::Horizontal => IVec2::new(bar.left() + i as i32, bar.bottom()),
+            };
+            let (glyph, fg) = match track.arrow_at(i as i32) {
+                Some(-1) => (back, style.fg),
+                Some(_) => (forward, style.fg),
+                None if thumb.contains(&i) => (Glyph::BlockFull, style.accent),
+                None => (Glyph::ShadeLight, style.fg),
+            };
+            term.put_char(p, glyph.to_char()).fg(fg).bg(style.bg);
+        }
+    }
+}
+
+/// The layout of a scrollbar along it's length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Track {
+    /// The length of the scrollbar in tiles, including arrows.
+    len: usize,
+    /// Whether the scrollbar is long enough to have arrows at each end.
+    arrows: bool,
+}
+
+impl Track {
+    fn new(len: usize) -> Self {
+        Self {
+            len,
+            arrows: len >= 3,
+        }
+    }
+
+    /// The range of the track the thumb can move along.
+    fn range(&self) -> Range<usize> {
+        if self.arrows {
+            1..self.len - 1
+        } else {
+            0..self.len
+        }
+    }
+
+    /// The direction of the arrow at a position, -1 for back and 1 for forward.
+    fn arrow_at(&self, pos: i32) -> Option<i32> {
+        if !self.arrows {
+            return None;
+        }
+        if pos == 0 {
+            Some(-1)
+        } else if pos == self.len as i32 - 1 {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// The range of tiles covered by the thumb.
+    fn thumb(&self, scroll: usize, view: usize, content: usize) -> Range<usize> {
+        let range = self.range();
+        let track_len = range.len();
+        if track_len == 0 || content == 0 {
+            return range;
+        }
+        let size = (track_len * view / content).clamp(1, track_len);
+        let max_scroll = content.saturating_sub(view);
+        let offset = ((track_len - size) * scroll.min(max_scroll) + max_scroll / 2)
+            .checked_div(max_scroll)
+            .unwrap_or(0);
+        let start = range.start + offset;
+        start..start + size
+    }
+
+    /// The scroll position which centers the thumb on a position along the
+    /// track.
+    fn scroll_at(&self, pos: i32, view: usize, content: usize) -> usize {
+        let range = self.range();
+        let track_len = range.len();
+        let size = (track_len * view / content.max(1)).clamp(1, track_len.max(1));
+        let free = track_len.saturating_sub(size);
+        let max_scroll = content.saturating_sub(view);
+        if free == 0 {
+            return 0;
+        }
+        let offset = (pos - range.start as i32 - size as i32 / 2).clamp(0, free as i32) as usize;
+        (offset * max_scroll + free / 2) / free
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumb() {
+        let track = Track::new(12);
+        // 10 track tiles, half the content visible
+        assert_eq!(1..6, track.thumb(0, 50, 100));
+        assert_eq!(6..11, track.thumb(50, 50, 100));
+        assert_eq!(4..9, track.thumb(30, 50, 100));
+        assert_eq!(0, track.scroll_at(1, 50, 100));
+        assert_eq!(50, track.scroll_at(10, 50, 100));
+        assert_eq!(Some(-1), track.arrow_at(0));
+        assert_eq!(Some(1), track.arrow_at(11));
+    }
+
+    #[test]
+    fn scroll_and_draw() {
+        let mut view = ScrollView::new([5, 10]);
+        for i in 0..10 {
+            view.canvas_mut()
+                .put_char([0, 9 - i], char::from_digit(i as u32, 10).unwrap());
+        }
+        let mut term = Terminal::new([6, 4]);
+        let rect = term.bounds();
+        let style = WidgetStyle::default();
+
+        view.draw(&mut term, rect, &WidgetInput::default(), &style);
+        assert_eq!(0..4, view.visible_rows());
+        assert_eq!('0', term.tile([0, 3]).glyph);
+        assert_eq!('▲', term.tile([5, 3]).glyph);
+        assert_eq!('▼', term.tile([5, 0]).glyph);
+
+        // Clicking the down arrow scrolls one row
+        let input = WidgetInput::default().click([5, 0]);
+        assert!(view.draw(&mut term, rect, &input, &style));
+        assert_eq!(1..5, view.visible_rows());
+        assert_eq!('1', term.tile([0, 3]).glyph);
+
+        view.scroll_to_row(9);
+        view.draw(&mut term, rect, &WidgetInput::default(), &style);
+        assert_eq!(6..10, view.visible_rows());
+        assert_eq!('9', term.tile([0, 0]).glyph);
+
+        // A rect outside the terminal draws nothing
+        let outside = GridRect::new([10, 10], [6, 4]);
+        let input = WidgetInput::default().click([5, 0]);
+        assert!(!view.draw(&mut term, outside, &input, &style));
+        assert_eq!('9', term.tile([0, 0]).glyph);
+        assert_eq!(GridRect::new([0, 0], [5, 4]), view.visible_rect());
+    }
+}
@@ -0,0 +1,571 @@
+//! A single line text input widget.
+
+use bevy::{
+    color::LinearRgba,
+    ecs::{
+        change_detection::{DetectChanges, DetectChangesMut},
+        component::Component,
+        entity::Entity,
+        event::{Event, EventReader, EventWriter},
+        system::{Query, Res},
+    },
+    input::{
+        keyboard::{Key, KeyCode, KeyboardInput},
+        ButtonInput, ButtonState,
+    },
+    math::IVec2,
+};
+use sark_grids::{GridPoint, Pivot, PivotedPoint};
+
+use crate::{color, string::StringFormatter, Terminal, TerminalFocus};
+
+/// The state of a single line text input.
+///
+/// This can be used directly by passing key presses to
+/// [TextInput::handle_key] and drawing with [TextInput::draw], or through the
+/// [TerminalTextInput] component which does both automatically.
+///
+/// # Example
+///
+/// ```
+/// use bevy::input::keyboard::Key;
+/// use bevy_ascii_terminal::{widget::{TextInput, TextInputModifiers}, *};
+///
+/// let mut input = TextInput::new("Hello");
+/// input.handle_key(&Key::Character(",".into()), TextInputModifiers::default());
+/// assert_eq!("Hello,", input.text());
+///
+/// let mut term = Terminal::new([10, 1]);
+/// input.draw(&mut term, [0, 0], 10, true);
+/// assert_eq!('H', term.tile([0, 0]).glyph);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    text: String,
+    /// The caret position as a char index.
+    caret: usize,
+    /// The other end of the selection from the caret, as a char index.
+    anchor: Option<usize>,
+    /// The index of the first visible char.
+    scroll: usize,
+    /// If true typed characters replace the character under the caret rather
+    /// than being inserted. Toggled with the `Insert` key.
+    pub overwrite: bool,
+    /// The maximum number of characters allowed in the input.
+    pub max_len: Option<usize>,
+    pub style: TextInputStyle,
+}
+
+/// Colors used when drawing a [TextInput]. The caret is drawn by inverting
+/// the colors of the tile under it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextInputStyle {
+    pub fg: LinearRgba,
+    pub bg: LinearRgba,
+    pub selection_bg: LinearRgba,
+}
+
+impl Default for TextInputStyle {
+    fn default() -> Self {
+        Self {
+            fg: color::WHITE,
+            bg: color::BLACK,
+            selection_bg: color::NAVY,
+        }
+    }
+}
+
+/// Modifier keys held while a key was pressed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextInputModifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+}
+
+impl TextInputModifiers {
+    pub fn from_input(input: &ButtonInput<KeyCode>) -> Self {
+        Self {
+            shift: input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+            ctrl: input.any_pressed([
+                KeyCode::ControlLeft,
+                KeyCode::ControlRight,
+                KeyCode::SuperLeft,
+                KeyCode::SuperRight,
+            ]),
+        }
+    }
+}
+
+/// The result of passing a key press to a [TextInput].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextInputAction {
+    /// The key didn't change the text, caret or selection.
+    None,
+    /// The caret or selection moved.
+    Moved,
+    /// The text was modified.
+    Changed,
+    /// The `Enter` key was pressed.
+    Submit,
+}
+
+impl TextInput {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let caret = text.chars().count();
+        Self {
+            text,
+            caret,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self.truncate_to_max();
+        self
+    }
+
+    pub fn with_style(mut self, style: TextInputStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the text, moving the caret to the end and clearing the
+    /// selection.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.truncate_to_max();
+        self.caret = self.len();
+        self.anchor = None;
+    }
+
+    /// Clear the text and selection.
+    pub fn clear(&mut self) {
+        self.set_text("");
+    }
+
+    /// The number of characters in the input.
+    pub fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// The caret position as a character index.
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// Move the caret to the given character index, optionally extending the
+    /// selection.
+    pub fn set_caret(&mut self, caret: usize, select: bool) {
+        let caret = caret.min(self.len());
+        if select {
+            self.anchor.get_or_insert(self.caret);
+        } else {
+            self.anchor = None;
+        }
+        self.caret = caret;
+        if self.anchor == Some(self.caret) {
+            self.anchor = None;
+        }
+    }
+
+    /// The selected range of character indices, if any.
+    pub fn selection(&self) -> Option<std::ops::Range<usize>> {
+        let anchor = self.anchor?;
+        Some(anchor.min(self.caret)..anchor.max(self.caret))
+    }
+
+    /// The selected text, if any.
+    pub fn selected_text(&self) -> Option<&str> {
+        let range = self.selection()?;
+        Some(&self.text[self.byte_index(range.start)..self.byte_index(range.end)])
+    }
+
+    pub fn select_all(&mut self) {
+        self.caret = self.len();
+        self.anchor = (self.caret > 0).then_some(0);
+    }
+
+    /// Insert a string at the caret, replacing the selection if there is one.
+    /// Control characters are ignored.
+    pub fn insert_str(&mut self, string: &str) {
+        self.delete_selection();
+        for ch in string.chars().filter(|c| !c.is_control()) {
+            if self.overwrite && self.caret < self.len() {
+                let i = self.byte_index(self.caret);
+                self.text.remove(i);
+            } else if self.max_len.is_some_and(|max| self.len() >= max) {
+                break;
+            }
+            let i = self.byte_index(self.caret);
+            self.text.insert(i, ch);
+            self.caret += 1;
+        }
+    }
+
+    /// Delete the selected text. Returns false if nothing was selected.
+    pub fn delete_selection(&mut self) -> bool {
+        let Some(range) = self.selection() else {
+            return false;
+        };
+        let (start, end) = (self.byte_index(range.start), self.byte_index(range.end));
+        self.text.replace_range(start..end, "");
+        self.caret = range.start;
+        self.anchor = None;
+        true
+    }
+
+    /// Delete the selection or the character before the caret. Returns true
+    /// if anything was deleted.
+    pub fn backspace(&mut self) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        if self.caret == 0 {
+            return false;
+        }
+        self.caret -= 1;
+        let i = self.byte_index(self.caret);
+        self.text.remove(i);
+        true
+    }
+
+    /// Delete the selection or the character after the caret. Returns true if
+    /// anything was deleted.
+    pub fn delete(&mut self) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        if self.caret >= self.len() {
+            return false;
+        }
+        let i = self.byte_index(self.caret);
+        self.text.remove(i);
+        true
+    }
+
+    /// Apply a key press to the input.
+    ///
+    /// Multi-character [Key::Character] values, for example from pasted or
+    /// IME composed text, are inserted in full.
+    pub fn handle_key(&mut self, key: &Key, modifiers: TextInputModifiers) -> TextInputAction {
+        let before = (self.caret, self.anchor);
+        let shift = modifiers.shift;
+        match key {
+            Key::Character(s) if modifiers.ctrl => {
+                if s.eq_ignore_ascii_case("a") {
+                    self.select_all();
+                }
+            }
+            Key::Character(s) => {
+                self.insert_str(s);
+                return TextInputAction::Changed;
+            }
+            Key::Space => {
+                self.insert_str(" ");
+                return TextInputAction::Changed;
+            }
+            Key::Backspace if self.backspace() => return TextInputAction::Changed,
+            Key::Delete if self.delete() => return TextInputAction::Changed,
+            Key::Insert => self.overwrite = !self.overwrite,
+            Key::Enter => return TextInputAction::Submit,
+            Key::ArrowLeft => match self.selection() {
+                Some(range) if !shift => self.set_caret(range.start, false),
+                _ => self.set_caret(self.caret.saturating_sub(1), shift),
+            },
+            Key::ArrowRight => match self.selection() {
+                Some(range) if !shift => self.set_caret(range.end, false),
+                _ => self.set_caret(self.caret + 1, shift),
+            },
+            Key::Home => self.set_caret(0, shift),
+            Key::End => self.set_caret(self.len(), shift),
+            _ => return TextInputAction::None,
+        }
+        if before == (self.caret, self.anchor) {
+            TextInputAction::None
+        } else {
+            TextInputAction::Moved
+        }
+    }
+
+    /// Draw the input to a single row of a terminal, scrolling horizontally
+    /// so the caret is always visible.
+    ///
+    /// The caret is only drawn if `focused` is true.
+    pub fn draw(
+        &mut self,
+        term: &mut Terminal,
+        xy: impl Into<PivotedPoint>,
+        width: usize,
+        focused: bool,
+    ) {
+        if width == 0 {
+            return;
+        }
+        // Find the absolute grid position so the string and caret line up
+        // regardless of pivot
+        let xy = xy
+            .into()
+            .with_default_pivot(Pivot::TopLeft)
+            .calculate(term.size());
+        let width = width.min(term.width().saturating_sub(xy.x.max(0) as usize));
+        if width == 0 || !term.bounds().contains_point(xy) {
+            return;
+        }
+
+        // Keep the caret in view. The caret can sit one past the last char
+        if self.caret < self.scroll {
+            self.scroll = self.caret;
+        } else if self.caret >= self.scroll + width {
+            self.scroll = self.caret + 1 - width;
+        }
+        self.scroll = self.scroll.min(self.len());
+
+        for x in 0..width {
+            term.put_char(xy + IVec2::new(x as i32, 0), ' ')
+                .fg(self.style.fg)
+                .bg(self.style.bg);
+        }
+        let visible: String = self.text.chars().skip(self.scroll).take(width).collect();
+        term.put_string(
+            xy.pivot(Pivot::BottomLeft),
+            visible.as_str().dont_word_wrap().fg(self.style.fg),
+        );
+
+        let visible_range = self.scroll..self.scroll + width;
+        if let Some(range) = self.selection() {
+            for i in range.filter(|i| visible_range.contains(i)) {
+                let p = xy + IVec2::new((i - self.scroll) as i32, 0);
+                term.put_bg_color(p, self.style.selection_bg);
+            }
+        }
+        if focused && visible_range.contains(&self.caret) {
+            let p = xy + IVec2::new((self.caret - self.scroll) as i32, 0);
+            let tile = term.tile_mut(p);
+            std::mem::swap(&mut tile.fg_color, &mut tile.bg_color);
+        }
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(char_index)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    fn truncate_to_max(&mut self) {
+        if let Some(max) = self.max_len {
+            let i = self.byte_index(max);
+            self.text.truncate(i);
+            self.caret = self.caret.min(self.len());
+        }
+    }
+}
+
+/// A component for a [TextInput] drawn into a terminal and driven by keyboard
+/// input events.
+///
+/// Key presses are only applied while `focused` is true. A
+/// [TextInputSubmitted] event is sent when `Enter` is pressed.
+///
+/// # Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_ascii_terminal::{widget::TerminalTextInput, *};
+///
+/// fn setup(mut commands: Commands) {
+///     let term = commands.spawn(Terminal::new([20, 3])).id();
+///     commands.spawn(TerminalTextInput::new(term, [1, 1], 18).focused());
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct TerminalTextInput {
+    /// The terminal entity to draw the input to.
+    pub terminal: Entity,
+    /// The position of the left of the input in the terminal. Uses the same
+    /// coordinates as [Terminal::put_string].
+    pub xy: PivotedPoint,
+    /// The width of the input in tiles.
+    pub width: usize,
+    pub focused: bool,
+    pub input: TextInput,
+}
+
+impl TerminalTextInput {
+    pub fn new(terminal: Entity, xy: impl Into<PivotedPoint>, width: usize) -> Self {
+        Self {
+            terminal,
+            xy: xy.into(),
+            width,
+            focused: false,
+            input: TextInput::default(),
+        }
+    }
+
+    pub fn focused(mut self) -> Self {
+        self.focused = true;
+        self
+    }
+
+    pub fn with_input(mut self, input: TextInput) -> Self {
+        self.input = input;
+        self
+    }
+}
+
+/// Sent when `Enter` is pressed in a focused [TerminalTextInput].
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct TextInputSubmitted {
+    /// The [TerminalTextInput] entity.
+    pub entity: Entity,
+    pub text: String,
+}
+
+pub(super) fn handle_text_input(
+    mut q_input: Query<(Entity, &mut TerminalTextInput)>,
+    mut keys: EventReader<KeyboardInput>,
+    key_state: Res<ButtonInput<KeyCode>>,
+    focus: Res<TerminalFocus>,
+    mut evt: EventWriter<TextInputSubmitted>,
+) {
+    if keys.is_empty() {
+        return;
+    }
+    let modifiers = TextInputModifiers::from_input(&key_state);
+    let presses: Vec<_> = keys
+        .read()
+        .filter(|k| k.state == ButtonState::Pressed)
+        .collect();
+    for (entity, mut text_input) in &mut q_input {
+        if !text_input.focused || !focus.has_focus(text_input.terminal) {
+            continue;
+        }
+        for key in &presses {
+            // Only mark the input as changed if the key did something
+            let input = &mut text_input.bypass_change_detection().input;
+            match input.handle_key(&key.logical_key, modifiers) {
+                TextInputAction::None => {}
+                TextInputAction::Submit => {
+                    let text = input.text().to_string();
+                    evt.send(TextInputSubmitted { entity, text });
+                }
+                _ => text_input.set_changed(),
+            }
+        }
+    }
+}
+
+pub(super) fn draw_text_input(
+    mut q_input: Query<&mut TerminalTextInput>,
+    mut q_term: Query<&mut Terminal>,
+) {
+    for mut text_input in &mut q_input {
+        let Ok(mut term) = q_term.get_mut(text_input.terminal) else {
+            continue;
+        };
+        // Redraw if the terminal was modified elsewhere, ie: cleared
+        if !text_input.is_changed() && !term.is_changed() {
+            continue;
+        }
+        // Scrolling is internal state, don't trigger another redraw
+        let text_input = text_input.bypass_change_detection();
+        let (xy, width, focused) = (text_input.xy, text_input.width, text_input.focused);
+        text_input.input.draw(&mut term, xy, width, focused);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(input: &mut TextInput, key: Key, shift: bool) -> TextInputAction {
+        input.handle_key(&key, TextInputModifiers { shift, ctrl: false })
+    }
+
+    #[test]
+    fn editing() {
+        let mut input = TextInput::new("héllo");
+        assert_eq!(5, input.caret());
+        press(&mut input, Key::Backspace, false);
+        assert_eq!("héll", input.text());
+        press(&mut input, Key::Home, false);
+        press(&mut input, Key::ArrowRight, false);
+        press(&mut input, Key::Delete, false);
+        assert_eq!("hll", input.text());
+        press(&mut input, Key::Character("ey".into()), false);
+        assert_eq!("heyll", input.text());
+
+        assert_eq!(TextInputAction::None, press(&mut input, Key::Insert, false));
+        press(&mut input, Key::Character("LL".into()), false);
+        assert_eq!("heyLL", input.text());
+        assert_eq!(
+            TextInputAction::Submit,
+            press(&mut input, Key::Enter, false)
+        );
+
+        assert_eq!(TextInputAction::None, press(&mut input, Key::Delete, false));
+        press(&mut input, Key::Home, false);
+        assert_eq!(
+            TextInputAction::None,
+            press(&mut input, Key::Backspace, false)
+        );
+        assert_eq!("heyLL", input.text());
+    }
+
+    #[test]
+    fn selection() {
+        let mut input = TextInput::new("hello world");
+        press(&mut input, Key::ArrowLeft, true);
+        press(&mut input, Key::ArrowLeft, true);
+        assert_eq!(Some("ld"), input.selected_text());
+        press(&mut input, Key::Character("!".into()), false);
+        assert_eq!("hello wor!", input.text());
+
+        press(&mut input, Key::Home, true);
+        assert_eq!(Some("hello wor!"), input.selected_text());
+        press(&mut input, Key::End, true);
+        assert_eq!(None, input.selected_text());
+        press(&mut input, Key::Home, true);
+        press(&mut input, Key::ArrowLeft, false);
+        assert_eq!(0, input.caret());
+        assert_eq!(None, input.selection());
+
+        input.select_all();
+        press(&mut input, Key::Backspace, false);
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn max_len() {
+        let mut input = TextInput::new("abc").with_max_len(4);
+        press(&mut input, Key::Character("def".into()), false);
+        assert_eq!("abcd", input.text());
+    }
+
+    #[test]
+    fn draw_scrolls() {
+        let mut term = Terminal::new([6, 1]);
+        let mut input = TextInput::new("abcdefgh");
+        input.draw(&mut term, [0, 0], 4, true);
+        let row: String = term.iter_row(0).take(4).map(|t| t.glyph).collect();
+        assert_eq!("fgh ", row);
+        // Caret is drawn inverted at the end of the text
+        let style = TextInputStyle::default();
+        assert_eq!(style.fg, term.tile([3, 0]).bg_color);
+
+        input.set_caret(0, false);
+        input.draw(&mut term, [0, 0], 4, true);
+        let row: String = term.iter_row(0).take(4).map(|t| t.glyph).collect();
+        assert_eq!("abcd", row);
+    }
+}
@@ -0,0 +1,278 @@
+//! Tooltips shown while hovering the tiles of a terminal.
+
+use std::sync::Arc;
+
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        system::{Commands, Local, Query, Res},
+    },
+    hierarchy::DespawnRecursiveExt,
+    math::{IVec2, Rect, UVec2},
+    render::{camera::OrthographicProjection, view::RenderLayers},
+    time::Time,
+    transform::components::GlobalTransform,
+};
+
+use crate::{
+    render::TerminalMeshPivot,
+    string::measure_string,
+    transform::{SetTerminalGridPosition, SetTerminalLayerPosition, TerminalTransform},
+    GridRect, Terminal, TerminalBorder, TerminalCamera, TerminalMouse, Tile,
+};
+
+use super::WidgetStyle;
+
+type TooltipFn = dyn Fn(IVec2) -> Option<String> + Send + Sync;
+
+/// A tooltip shown while the cursor hovers a terminal.
+///
+/// The tooltip is attached to a terminal entity and optionally limited to a
+/// rect of that terminal. While the cursor rests over it a temporary bordered
+/// terminal is spawned next to the cursor, sized to fit the wrapped content and
+/// kept inside the camera's view. It's despawned as soon as the cursor moves
+/// off the tooltip's area or onto a tile with different content.
+///
+/// Content can either be a fixed string or built from the hovered tile with
+/// [Tooltip::from_fn]. A tooltip entity may be spawned on it's own or on the
+/// terminal it belongs to, and any number of tooltips can point at the same
+/// terminal. The first one with content for the hovered tile is shown.
+///
+/// # Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_ascii_terminal::{widget::*, *};
+///
+/// fn setup(mut commands: Commands) {
+///     let term = commands.spawn(Terminal::new([40, 20])).id();
+///     commands.spawn(Tooltip::from_fn(term, |tile| {
+///         (tile.y == 0).then(|| format!("The ground at column {}", tile.x))
+///     }));
+///     commands.spawn(
+///         Tooltip::text(term, "A button that does nothing")
+///             .with_rect(GridRect::new([1, 18], [8, 1])),
+///     );
+/// }
+/// ```
+#[derive(Component, Clone)]
+pub struct Tooltip {
+    /// The terminal the tooltip belongs to.
+    pub terminal: Entity,
+    /// The area of the terminal that shows the tooltip. If [None] the whole
+    /// terminal will show it.
+    pub rect: Option<GridRect>,
+    /// The maximum width of the tooltip content before it's wrapped.
+    pub max_width: usize,
+    /// How long the cursor must rest over the tooltip area before it's shown,
+    /// in seconds.
+    pub delay: f32,
+    pub style: WidgetStyle,
+    content: TooltipContent,
+}
+
+#[derive(Clone)]
+enum TooltipContent {
+    Text(String),
+    Fn(Arc<TooltipFn>),
+}
+
+impl Tooltip {
+    fn new(terminal: Entity, content: TooltipContent) -> Self {
+        Self {
+            terminal,
+            rect: None,
+            max_width: 30,
+            delay: 0.3,
+            style: WidgetStyle::default(),
+            content,
+        }
+    }
+
+    /// A tooltip that always shows the same text.
+    pub fn text(terminal: Entity, text: impl Into<String>) -> Self {
+        Self::new(terminal, TooltipContent::Text(text.into()))
+    }
+
+    /// A tooltip built from the hovered tile. Tiles the function returns [None]
+    /// for show no tooltip.
+    pub fn from_fn(
+        terminal: Entity,
+        content: impl Fn(IVec2) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        Self::new(terminal, TooltipContent::Fn(Arc::new(content)))
+    }
+
+    /// Limit the tooltip to an area of the terminal.
+    pub fn with_rect(mut self, rect: GridRect) -> Self {
+        self.rect = Some(rect);
+        self
+    }
+
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = max_width.max(1);
+        self
+    }
+
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn with_style(mut self, style: WidgetStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// The tooltip content for a tile of it's terminal, or [None] if the tile
+    /// shouldn't show a tooltip.
+    pub fn content(&self, tile: IVec2) -> Option<String> {
+        if self.rect.is_some_and(|r| !r.contains_point(tile)) {
+            return None;
+        }
+        match &self.content {
+            TooltipContent::Text(text) => Some(text.clone()),
+            TooltipContent::Fn(f) => f(tile),
+        }
+        // Whitespace and control characters alone would draw nothing
+        .filter(|s| s.chars().any(|c| !c.is_whitespace() && !c.is_control()))
+    }
+
+    /// Build the terminal for some tooltip content, sized to fit the content
+    /// wrapped to [Tooltip::max_width]. The terminal is always at least one
+    /// tile in size.
+    pub fn build_terminal(&self, content: &str) -> Terminal {
+        let size = measure_string(content, self.max_width.max(1))
+            .size()
+            .max(UVec2::ONE);
+        let clear = Tile::new(' ', self.style.fg, self.style.bg);
+        let mut term = Terminal::new(size).with_clear_tile(clear);
+        term.put_string([0, 0], content);
+        term
+    }
+}
+
+/// The currently shown tooltip.
+#[derive(Default)]
+pub(super) struct ActiveTooltip {
+    /// The tooltip entity the content was built from.
+    source: Option<Entity>,
+    content: String,
+    hover_start: f32,
+    /// The spawned tooltip terminal.
+    terminal: Option<Entity>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn update_tooltips(
+    q_tooltip: Query<(Entity, &Tooltip)>,
+    q_anchor: Query<(&TerminalTransform, &GlobalTransform, Option<&RenderLayers>)>,
+    q_cam: Query<(&TerminalCamera, &OrthographicProjection, &GlobalTransform)>,
+    mouse: Res<TerminalMouse>,
+    time: Res<Time>,
+    mut active: Local<ActiveTooltip>,
+    mut commands: Commands,
+) {
+    let hovered = mouse.hovered();
+    // Keep the tooltip if it ended up under the cursor after being clamped
+    if active.terminal.is_some() && hovered.map(|(e, _)| e) == active.terminal {
+        return;
+    }
+    let found = hovered.and_then(|(term, tile)| {
+        q_tooltip
+            .iter()
+            .filter(|(_, t)| t.terminal == term)
+            .find_map(|(e, t)| t.content(tile).map(|s| (e, t, tile, s)))
+    });
+
+    let source = found.as_ref().map(|(e, ..)| *e);
+    let content = found.as_ref().map(|(.., s)| s.as_str()).unwrap_or_default();
+    if source != active.source || content != active.content {
+        if let Some(entity) = active.terminal.take() {
+            if let Some(cmd) = commands.get_entity(entity) {
+                cmd.despawn_recursive();
+            }
+        }
+        active.source = source;
+        active.content = content.to_string();
+        active.hover_start = time.elapsed_secs();
+    }
+
+    let Some((_, tooltip, tile, content)) = found else {
+        return;
+    };
+    if active.terminal.is_some() || time.elapsed_secs() - active.hover_start < tooltip.delay {
+        return;
+    }
+    let Some((data, transform, layers)) = q_anchor
+        .get(tooltip.terminal)
+        .ok()
+        .and_then(|(t, gt, layers)| t.cached_data.as_ref().map(|d| (d, gt, layers)))
+    else {
+        return;
+    };
+
+    let term = tooltip.build_terminal(&content);
+    // Include the border in the size used for positioning
+    let size = term.size().as_ivec2() + 2;
+    let origin = data.world_pos.truncate() + data.local_inner_mesh_bounds.min;
+    let tile_size = data.world_tile_size;
+    let tile_pos = (origin / tile_size).round().as_ivec2() + tile;
+    // Top left corner of the border at the bottom right corner of the tile
+    let mut pos = tile_pos + IVec2::new(1, 0);
+    let view = q_cam
+        .iter()
+        .find(|(cam, ..)| cam.cursor_world_pos().is_some())
+        .map(|(_, proj, gt)| {
+            let center = gt.translation().truncate();
+            Rect::from_corners(proj.area.min + center, proj.area.max + center)
+        });
+    if let Some(view) = view {
+        let min = (view.min / tile_size).ceil().as_ivec2();
+        let max = (view.max / tile_size).floor().as_ivec2();
+        // Flip to the other side of the tile rather than cover it
+        if pos.x + size.x > max.x {
+            pos.x = tile_pos.x - size.x;
+        }
+        if pos.y - size.y < min.y {
+            pos.y = tile_pos.y + 1 + size.y;
+        }
+        pos.x = pos.x.min(max.x - size.x).max(min.x);
+        pos.y = pos.y.max(min.y + size.y).min(max.y);
+    }
+    let layer = transform.translation().z.round() as i32 + 1;
+    let mut entity = commands.spawn((
+        term,
+        TerminalBorder::single_line(),
+        TerminalMeshPivot::TopLeft,
+        SetTerminalGridPosition(pos),
+        SetTerminalLayerPosition(layer),
+    ));
+    // Render with the same cameras as the terminal it belongs to
+    if let Some(layers) = layers {
+        entity.insert(layers.clone());
+    }
+    active.terminal = Some(entity.id());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content() {
+        let tooltip = Tooltip::from_fn(Entity::from_raw(0), |p| (p.x > 0).then(|| p.to_string()))
+            .with_rect(GridRect::new([0, 0], [3, 3]));
+        assert_eq!(None, tooltip.content(IVec2::new(0, 0)));
+        assert!(tooltip.content(IVec2::new(1, 1)).is_some());
+        assert_eq!(None, tooltip.content(IVec2::new(4, 1)));
+    }
+
+    #[test]
+    fn control_characters() {
+        let tooltip = Tooltip::text(Entity::from_raw(0), "\u{7}\t");
+        assert_eq!(None, tooltip.content(IVec2::ZERO));
+        assert_eq!(UVec2::ONE, tooltip.build_terminal("\u{7}").size());
+    }
+}
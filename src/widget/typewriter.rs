@@ -0,0 +1,344 @@
+//! Text revealed one character at a time, for dialogue boxes.
+
+use bevy::{
+    ecs::{
+        change_detection::{DetectChanges, DetectChangesMut},
+        component::Component,
+        entity::Entity,
+        event::{Event, EventReader, EventWriter},
+        system::{Query, Res},
+    },
+    input::{keyboard::KeyCode, mouse::MouseButton, ButtonInput},
+    time::Time,
+};
+use sark_grids::GridRect;
+
+use crate::{
+    string::{StringIter, TerminalString},
+    Terminal, TerminalFocus, TerminalMouseEvent, TerminalMouseEventKind,
+};
+
+use super::clip_rect;
+
+/// A component which reveals a string in a region of a terminal one character
+/// at a time.
+///
+/// The string is wrapped against it's full length before being revealed, so
+/// words never jump to the next line partway through. The reveal pauses
+/// briefly after punctuation and can be skipped to the end with
+/// [Typewriter::skip]. If `skip_on_input` is set, pressing enter or space
+/// while the terminal has [TerminalFocus], or clicking the terminal, will skip
+/// it.
+///
+/// A [TypewriterFinished] event is sent once all of the text is revealed.
+///
+/// # Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_ascii_terminal::{widget::*, *};
+///
+/// fn setup(mut commands: Commands) {
+///     let term = commands.spawn(Terminal::new([30, 6])).id();
+///     commands.spawn(Typewriter::new(term, "Halt! Who goes there?").with_speed(20.0));
+/// }
+///
+/// fn on_finished(mut evt: EventReader<TypewriterFinished>) {
+///     for _ in evt.read() {
+///         info!("Press enter to continue");
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct Typewriter {
+    /// The terminal the text is drawn to.
+    pub terminal: Entity,
+    /// The region of the terminal the text is drawn in. If `None` the text
+    /// will fill the entire terminal.
+    pub rect: Option<GridRect>,
+    /// The number of characters revealed per second. If this is zero or less
+    /// the text is revealed immediately.
+    pub speed: f32,
+    /// An extra delay in seconds after the end of a sentence. Half of this
+    /// delay is used after commas, colons and semicolons.
+    pub punctuation_pause: f32,
+    /// If true, pressing enter or space or clicking the terminal reveals the
+    /// rest of the text.
+    pub skip_on_input: bool,
+    text: TerminalString<String>,
+    /// The number of characters revealed so far.
+    revealed: usize,
+    /// Time in seconds until the next character is revealed.
+    wait: f32,
+    finished: bool,
+}
+
+/// Sent when a [Typewriter] has revealed all of it's text.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypewriterFinished {
+    /// The typewriter entity.
+    pub entity: Entity,
+}
+
+impl Typewriter {
+    pub fn new<T: AsRef<str>>(terminal: Entity, text: impl Into<TerminalString<T>>) -> Self {
+        Self {
+            terminal,
+            rect: None,
+            speed: 30.0,
+            punctuation_pause: 0.3,
+            skip_on_input: true,
+            text: to_owned_string(text),
+            revealed: 0,
+            wait: 0.0,
+            finished: false,
+        }
+    }
+
+    pub fn with_rect(mut self, rect: GridRect) -> Self {
+        self.rect = Some(rect);
+        self
+    }
+
+    pub fn with_speed(mut self, chars_per_second: f32) -> Self {
+        self.speed = chars_per_second;
+        self
+    }
+
+    pub fn with_punctuation_pause(mut self, seconds: f32) -> Self {
+        self.punctuation_pause = seconds;
+        self
+    }
+
+    /// Replace the text and start revealing it from the beginning.
+    pub fn set_text<T: AsRef<str>>(&mut self, text: impl Into<TerminalString<T>>) {
+        self.text = to_owned_string(text);
+        self.restart();
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text.string
+    }
+
+    /// Start revealing the text from the beginning.
+    pub fn restart(&mut self) {
+        self.revealed = 0;
+        self.wait = 0.0;
+        self.finished = false;
+    }
+
+    /// Reveal the rest of the text. The typewriter will be finished the next
+    /// time it's updated.
+    pub fn skip(&mut self) {
+        self.revealed = usize::MAX;
+    }
+
+    /// The number of characters revealed so far.
+    pub fn revealed(&self) -> usize {
+        self.revealed
+    }
+
+    /// True once all of the text has been revealed.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The region of the terminal the text is drawn in. This is empty if the
+    /// rect is outside the terminal.
+    pub fn region(&self, term: &Terminal) -> GridRect {
+        self.rect
+            .map_or(term.bounds(), |r| clip_rect(r, term.bounds()))
+    }
+
+    /// The full text as it will be written to the given region.
+    fn iter(&self, region: GridRect) -> StringIter<'_> {
+        StringIter::new(
+            &self.text.string,
+            region,
+            [0, 0],
+            Some(self.text.formatting),
+            Some(self.text.decoration),
+        )
+    }
+
+    /// The delay after revealing a character.
+    fn delay(&self, ch: char) -> f32 {
+        let pause = match ch {
+            '.' | '!' | '?' => self.punctuation_pause,
+            ',' | ';' | ':' => self.punctuation_pause * 0.5,
+            _ => 0.0,
+        };
+        1.0 / self.speed + pause
+    }
+
+    /// Advance the reveal by the given time in seconds. Returns true if any
+    /// characters were revealed.
+    pub fn advance(&mut self, delta: f32, region: GridRect) -> bool {
+        if self.finished || region.width() == 0 || region.height() == 0 {
+            return false;
+        }
+        let total = self.iter(region).count();
+        let mut revealed = self.revealed.min(total);
+        let mut wait = self.wait - delta;
+        if self.speed <= 0.0 {
+            revealed = total;
+        }
+        for (_, (ch, _, _)) in self.iter(region).skip(revealed) {
+            if wait > 0.0 {
+                break;
+            }
+            revealed += 1;
+            wait += self.delay(ch);
+        }
+        let changed = revealed != self.revealed;
+        self.revealed = revealed;
+        self.wait = wait;
+        self.finished = revealed >= total;
+        changed
+    }
+
+    /// Clear the typewriter's region of the terminal and draw the revealed
+    /// text.
+    pub fn draw(&self, term: &mut Terminal) {
+        let region = self.region(term);
+        if region.width() == 0 || region.height() == 0 {
+            return;
+        }
+        let clear_tile = term.clear_tile();
+        for p in region.iter_points() {
+            term.put_tile(p, clear_tile);
+        }
+        let clear_colors = self.text.decoration.clear_colors;
+        for (p, (ch, fg, bg)) in self.iter(region).take(self.revealed) {
+            let tile = term.tile_mut(p);
+            tile.glyph = ch;
+            if clear_colors {
+                continue;
+            }
+            if let Some(fg) = fg {
+                tile.fg_color = fg;
+            }
+            if let Some(bg) = bg {
+                tile.bg_color = bg;
+            }
+        }
+    }
+}
+
+fn to_owned_string<T: AsRef<str>>(text: impl Into<TerminalString<T>>) -> TerminalString<String> {
+    let text: TerminalString<T> = text.into();
+    TerminalString {
+        string: text.string.as_ref().to_string(),
+        decoration: text.decoration,
+        formatting: text.formatting,
+    }
+}
+
+pub(super) fn skip_typewriters(
+    mut q_writer: Query<&mut Typewriter>,
+    mut mouse_events: EventReader<TerminalMouseEvent>,
+    keys: Res<ButtonInput<KeyCode>>,
+    focus: Res<TerminalFocus>,
+) {
+    let clicked: Vec<_> = mouse_events
+        .read()
+        .filter(|e| matches!(e.kind, TerminalMouseEventKind::Click(MouseButton::Left)))
+        .map(|e| e.entity)
+        .collect();
+    let key = keys.any_just_pressed([KeyCode::Enter, KeyCode::Space]);
+    if !key && clicked.is_empty() {
+        return;
+    }
+    for mut writer in &mut q_writer {
+        if !writer.skip_on_input || writer.finished {
+            continue;
+        }
+        let terminal = writer.terminal;
+        if (key && focus.has_focus(terminal)) || clicked.contains(&terminal) {
+            writer.skip();
+        }
+    }
+}
+
+pub(super) fn update_typewriters(
+    mut q_writer: Query<(Entity, &mut Typewriter)>,
+    mut q_term: Query<&mut Terminal>,
+    time: Res<Time>,
+    mut evt: EventWriter<TypewriterFinished>,
+) {
+    for (entity, mut writer) in &mut q_writer {
+        let Ok(mut term) = q_term.get_mut(writer.terminal) else {
+            continue;
+        };
+        let was_finished = writer.finished;
+        let region = writer.region(&term);
+        // Only count as a change when characters are revealed
+        if writer
+            .bypass_change_detection()
+            .advance(time.delta_secs(), region)
+        {
+            writer.set_changed();
+        }
+        if writer.finished && !was_finished {
+            evt.send(TypewriterFinished { entity });
+        }
+        // Redraw if the terminal was modified elsewhere, ie: cleared
+        if writer.is_changed() || term.is_changed() {
+            writer.draw(&mut term);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reveal() {
+        let region = GridRect::new([0, 0], [10, 1]);
+        let mut writer = Typewriter::new(Entity::PLACEHOLDER, "a. b")
+            .with_speed(10.0)
+            .with_punctuation_pause(1.0);
+        assert!(writer.advance(0.0, region));
+        assert_eq!(1, writer.revealed());
+        writer.advance(0.1, region);
+        assert_eq!(2, writer.revealed());
+        assert!(!writer.advance(0.5, region));
+        writer.advance(0.7, region);
+        assert_eq!(3, writer.revealed());
+        assert!(!writer.is_finished());
+
+        writer.skip();
+        writer.advance(0.0, region);
+        assert_eq!(4, writer.revealed());
+        assert!(writer.is_finished());
+    }
+
+    #[test]
+    fn wrap_full_string() {
+        let mut term = Terminal::new([5, 2]);
+        let region = term.bounds();
+        let mut writer = Typewriter::new(Entity::PLACEHOLDER, "aaa bbb").with_speed(0.0);
+        writer.advance(0.0, region);
+        writer.revealed = 4;
+        writer.draw(&mut term);
+        assert_eq!('a', term.tile([2, 1]).glyph);
+        assert_eq!(' ', term.tile([4, 1]).glyph);
+        assert_eq!('b', term.tile([0, 0]).glyph);
+        assert_eq!(' ', term.tile([1, 0]).glyph);
+    }
+
+    #[test]
+    fn rect_outside_terminal() {
+        let mut term = Terminal::new([10, 4]);
+        let mut writer = Typewriter::new(Entity::PLACEHOLDER, "Hello")
+            .with_rect(GridRect::new([6, 2], [4, 2]))
+            .with_speed(0.0);
+        term.resize([4, 2]);
+        let region = writer.region(&term);
+        assert_eq!(0, region.width() * region.height());
+        assert!(!writer.advance(1.0, region));
+        writer.draw(&mut term);
+        assert!(term.tiles().iter().all(|t| *t == term.clear_tile()));
+    }
+}